@@ -1,8 +1,11 @@
 //! A high-level way to load collections of asset handles as resources.
 
 use std::collections::VecDeque;
+use std::marker::PhantomData;
 
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
 use bevy::prelude::*;
+use serde::de::DeserializeOwned;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<ResourceHandles>();
@@ -69,3 +72,60 @@ fn load_resource_assets(world: &mut World) {
         });
     });
 }
+
+pub trait RegisterRonAsset {
+    /// Registers `T` as an [`Asset`] that is deserialized from RON files with the given
+    /// extensions, e.g. `"items.ron"`.
+    fn register_ron_asset<T: Asset + DeserializeOwned>(
+        &mut self,
+        extensions: &'static [&'static str],
+    ) -> &mut Self;
+}
+
+impl RegisterRonAsset for App {
+    fn register_ron_asset<T: Asset + DeserializeOwned>(
+        &mut self,
+        extensions: &'static [&'static str],
+    ) -> &mut Self {
+        self.init_asset::<T>()
+            .register_asset_loader(RonAssetLoader::<T> {
+                extensions,
+                _marker: PhantomData,
+            })
+    }
+}
+
+/// Loads any deserializable [`Asset`] from a RON file.
+struct RonAssetLoader<T> {
+    extensions: &'static [&'static str],
+    _marker: PhantomData<fn() -> T>,
+}
+
+#[derive(thiserror::Error, Debug)]
+enum RonAssetLoaderError {
+    #[error("Could not read asset: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse RON: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl<T: Asset + DeserializeOwned> AssetLoader for RonAssetLoader<T> {
+    type Asset = T;
+    type Settings = ();
+    type Error = RonAssetLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<T, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.extensions
+    }
+}
@@ -4,6 +4,8 @@ use bevy::{
     dev_tools::states::log_transitions, input::common_conditions::input_just_pressed, prelude::*,
 };
 
+use crate::game::ai::behaviour::BehaviourTree;
+use crate::game::ai::perception::Awareness;
 use crate::screens::Screen;
 
 pub(super) fn plugin(app: &mut App) {
@@ -15,10 +17,74 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         toggle_debug_ui.run_if(input_just_pressed(TOGGLE_KEY)),
     );
+
+    // Show which branch of each behaviour tree is running.
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_behaviour_panel);
+    app.add_systems(
+        Update,
+        (
+            toggle_behaviour_panel.run_if(input_just_pressed(BEHAVIOUR_TOGGLE_KEY)),
+            update_behaviour_panel,
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
 }
 
 const TOGGLE_KEY: KeyCode = KeyCode::Backquote;
 
+const BEHAVIOUR_TOGGLE_KEY: KeyCode = KeyCode::F3;
+
 fn toggle_debug_ui(mut options: ResMut<UiDebugOptions>) {
     options.toggle();
 }
+
+#[derive(Component, Debug, Clone, Copy)]
+struct BehaviourPanel;
+
+fn spawn_behaviour_panel(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Behaviour Panel"),
+        BehaviourPanel,
+        Text::default(),
+        TextFont::from_font_size(14.0),
+        Node {
+            position_type: PositionType::Absolute,
+            right: px(8),
+            top: px(8),
+            ..default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(0.6)),
+        Visibility::Hidden,
+        GlobalZIndex(2),
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
+
+fn toggle_behaviour_panel(mut query: Query<&mut Visibility, With<BehaviourPanel>>) {
+    for mut visibility in &mut query {
+        visibility.toggle_visible_hidden();
+    }
+}
+
+fn update_behaviour_panel(
+    tree_query: Query<(Entity, Option<&Name>, &BehaviourTree, Option<&Awareness>)>,
+    mut panel_query: Query<(&mut Text, &Visibility), With<BehaviourPanel>>,
+) {
+    for (mut text, visibility) in &mut panel_query {
+        if visibility == Visibility::Hidden {
+            continue;
+        }
+
+        text.0 = tree_query
+            .iter()
+            .map(|(entity, name, tree, awareness)| {
+                let name = name.map_or_else(|| entity.to_string(), |name| name.to_string());
+                let awareness = awareness.map_or_else(String::new, |awareness| {
+                    format!(" {:?} ({:.2})", awareness.level, awareness.suspicion)
+                });
+                format!("{name} ({entity}){awareness}: {}", tree.active_branch())
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+}
@@ -0,0 +1,794 @@
+//! A small behaviour tree runtime.
+//!
+//! Trees are defined in `.bt.ron` asset files as a [`BehaviourTreeDefinition`] and run by
+//! the [`BehaviourTree`] component. Every frame the tree is ticked from the root, so
+//! higher priority branches of a [`BehaviourNode::Selector`] can interrupt lower ones.
+//! Leaves read what they need from the entity's [`Blackboard`] and act by setting the
+//! [`MovementController`] intent or requesting a change of [`CharacterState`].
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::asset_tracking::RegisterRonAsset;
+use crate::game::ai::perception::{Awareness, AwarenessLevel};
+use crate::game::character::health::{DamageType, Health, HealthEvent, HealthEventType};
+use crate::game::character::stats::Stats;
+use crate::game::character::{CharacterState, CharacterStateEvent, Facing};
+use crate::game::grid::TileMap;
+use crate::game::grid::coords::WorldPosition;
+use crate::game::grid::pathfinding::{NavPath, find_path};
+use crate::game::physics::movement::MovementController;
+use crate::game::projectile::{RangedAttack, SpawnProjectileEvent};
+use crate::screens::Screen;
+use crate::{AppSystems, PausableSystems};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ron_asset::<BehaviourTreeDefinition>(&["bt.ron"]);
+
+    app.add_systems(
+        Update,
+        tick_behaviour_trees
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::RecordInput)
+            .in_set(PausableSystems),
+    );
+}
+
+/// How often a [`BehaviourNode::MoveTo`] recalculates its path.
+const REPATH_INTERVAL: f32 = 0.5;
+/// How far in front of a character its projectiles are spawned, so they clear its collider.
+const MUZZLE_OFFSET: f32 = 0.5;
+
+/// A behaviour tree as written in an asset file.
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct BehaviourTreeDefinition {
+    pub root: BehaviourNode,
+}
+
+/// A node of a behaviour tree. Targets are blackboard keys holding either an
+/// entity or a position.
+#[derive(Debug, Clone, Deserialize)]
+pub enum BehaviourNode {
+    /// Ticks children in order until one succeeds or is running.
+    Selector(Vec<BehaviourNode>),
+    /// Ticks children in order until one fails or is running.
+    Sequence(Vec<BehaviourNode>),
+
+    /// Swaps success and failure of its child.
+    Invert(Box<BehaviourNode>),
+    /// Succeeds whenever its child finishes, even if it failed.
+    Succeed(Box<BehaviourNode>),
+    /// Fails without ticking its child until `seconds` have passed since the child last
+    /// succeeded.
+    Cooldown {
+        seconds: f32,
+        child: Box<BehaviourNode>,
+    },
+
+    /// Succeeds if the blackboard has a value for the key.
+    IsSet(String),
+    /// Succeeds if the target is within `radius`.
+    InRange { target: String, radius: f32 },
+    /// Succeeds if the character's health is below the given fraction of its maximum.
+    HealthBelow(f32),
+    /// Succeeds if the character's [`Awareness`] is at least the given level.
+    Aware(AwarenessLevel),
+
+    /// Walks towards the target, succeeding once within `range`.
+    MoveTo { target: String, range: f32 },
+    /// Attacks the target entity if it is within `range`, succeeding once the attack
+    /// has finished.
+    Attack {
+        target: String,
+        range: f32,
+        damage: usize,
+        damage_type: DamageType,
+        duration: f32,
+    },
+    /// Fires the character's [`RangedAttack`] at the target if it is within `range`,
+    /// succeeding once the shot has finished. Fails for characters without one.
+    Shoot { target: String, range: f32 },
+    /// Stands still for `seconds`.
+    Wait { seconds: f32 },
+    /// Walks away from the target, succeeding once at least `distance` away. Takes the
+    /// path to the point `distance` away from the target on the far side of the character.
+    Flee { from: String, distance: f32 },
+    /// Walks between the positions of the route, waiting at each one. Never finishes.
+    Patrol { route: String, wait: f32 },
+}
+
+impl BehaviourNode {
+    fn name(&self) -> &'static str {
+        match self {
+            BehaviourNode::Selector(_) => "Selector",
+            BehaviourNode::Sequence(_) => "Sequence",
+            BehaviourNode::Invert(_) => "Invert",
+            BehaviourNode::Succeed(_) => "Succeed",
+            BehaviourNode::Cooldown { .. } => "Cooldown",
+            BehaviourNode::IsSet(_) => "IsSet",
+            BehaviourNode::InRange { .. } => "InRange",
+            BehaviourNode::HealthBelow(_) => "HealthBelow",
+            BehaviourNode::Aware(_) => "Aware",
+            BehaviourNode::MoveTo { .. } => "MoveTo",
+            BehaviourNode::Attack { .. } => "Attack",
+            BehaviourNode::Shoot { .. } => "Shoot",
+            BehaviourNode::Wait { .. } => "Wait",
+            BehaviourNode::Flee { .. } => "Flee",
+            BehaviourNode::Patrol { .. } => "Patrol",
+        }
+    }
+
+    fn children(&self) -> Vec<&BehaviourNode> {
+        match self {
+            BehaviourNode::Selector(children) | BehaviourNode::Sequence(children) => {
+                children.iter().collect()
+            }
+            BehaviourNode::Invert(child)
+            | BehaviourNode::Succeed(child)
+            | BehaviourNode::Cooldown { child, .. } => vec![child.as_ref()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum BehaviourStatus {
+    Success,
+    Failure,
+    Running,
+}
+
+/// Per-entity memory shared between the nodes of a behaviour tree and the systems
+/// that sense the world.
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct Blackboard {
+    values: HashMap<String, BlackboardValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub enum BlackboardValue {
+    Entity(Entity),
+    Position(Vec3),
+    Route(Vec<Vec3>),
+    Number(f32),
+    Flag(bool),
+}
+
+impl Blackboard {
+    pub fn with(mut self, key: impl Into<String>, value: BlackboardValue) -> Self {
+        self.set(key, value);
+        self
+    }
+
+    pub fn set(&mut self, key: impl Into<String>, value: BlackboardValue) {
+        self.values.insert(key.into(), value);
+    }
+
+    pub fn get(&self, key: &str) -> Option<&BlackboardValue> {
+        self.values.get(key)
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.values.remove(key);
+    }
+
+    pub fn entity(&self, key: &str) -> Option<Entity> {
+        match self.get(key)? {
+            BlackboardValue::Entity(entity) => Some(*entity),
+            _ => None,
+        }
+    }
+}
+
+/// Runs a [`BehaviourTreeDefinition`] for the entity it is attached to.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct BehaviourTree {
+    definition: Handle<BehaviourTreeDefinition>,
+    #[reflect(ignore)]
+    root: Option<NodeRuntime>,
+    tick: u64,
+    time: f32,
+}
+
+impl BehaviourTree {
+    pub fn new(definition: Handle<BehaviourTreeDefinition>) -> Self {
+        Self {
+            definition,
+            root: None,
+            tick: 0,
+            time: 0.0,
+        }
+    }
+
+    /// The nodes that ran on the last tick, from the root down, along with the status
+    /// of the deepest one. Built on demand, since it is only needed for debugging.
+    pub fn active_branch(&self) -> String {
+        let mut names = Vec::new();
+        let mut status = None;
+        let mut node = self.root.as_ref();
+
+        while let Some(current) = node.filter(|node| node.state.last_tick == self.tick) {
+            names.push(current.name);
+            status = current.state.status;
+
+            // The last child ticked is the one that decided the parent's status.
+            node = current
+                .children
+                .iter()
+                .rev()
+                .find(|child| child.state.last_tick == self.tick);
+        }
+
+        match status {
+            Some(status) => format!("{} [{status:?}]", names.join(" > ")),
+            None => String::new(),
+        }
+    }
+}
+
+/// A node of a running tree along with the state it keeps between ticks.
+struct NodeRuntime {
+    name: &'static str,
+    node: BehaviourNode,
+    children: Vec<NodeRuntime>,
+    state: NodeState,
+}
+
+#[derive(Default)]
+struct NodeState {
+    /// The tree tick this node was last ticked on, used to detect (re)starts.
+    last_tick: u64,
+    status: Option<BehaviourStatus>,
+    /// Tree time at which the node was (re)started.
+    started_at: f32,
+    /// Tree time after which a cooldown is over.
+    ready_at: f32,
+    /// Leaf specific progress, e.g. whether an attack has been started.
+    flag: bool,
+    /// Leaf specific progress which survives restarts, e.g. the next patrol point.
+    index: usize,
+    /// Number of repath intervals elapsed when the path was last recalculated.
+    repaths: usize,
+}
+
+impl NodeRuntime {
+    fn new(node: &BehaviourNode) -> Self {
+        Self {
+            name: node.name(),
+            node: node.clone(),
+            children: node.children().into_iter().map(NodeRuntime::new).collect(),
+            state: NodeState::default(),
+        }
+    }
+
+    fn tick(&mut self, context: &mut BehaviourContext) -> BehaviourStatus {
+        // Restart nodes which were not running on the previous tick.
+        let was_running = self.state.last_tick + 1 == context.tick
+            && self.state.status == Some(BehaviourStatus::Running);
+
+        if !was_running {
+            self.state.started_at = context.time;
+            self.state.flag = false;
+        }
+        self.state.last_tick = context.tick;
+
+        let status = self.evaluate(context);
+        self.state.status = Some(status);
+        status
+    }
+
+    fn evaluate(&mut self, context: &mut BehaviourContext) -> BehaviourStatus {
+        let elapsed = context.time - self.state.started_at;
+
+        match &self.node {
+            BehaviourNode::Selector(_) => {
+                for child in &mut self.children {
+                    match child.tick(context) {
+                        BehaviourStatus::Failure => continue,
+                        status => return status,
+                    }
+                }
+                BehaviourStatus::Failure
+            }
+            BehaviourNode::Sequence(_) => {
+                for child in &mut self.children {
+                    match child.tick(context) {
+                        BehaviourStatus::Success => continue,
+                        status => return status,
+                    }
+                }
+                BehaviourStatus::Success
+            }
+            BehaviourNode::Invert(_) => match self.children[0].tick(context) {
+                BehaviourStatus::Success => BehaviourStatus::Failure,
+                BehaviourStatus::Failure => BehaviourStatus::Success,
+                BehaviourStatus::Running => BehaviourStatus::Running,
+            },
+            BehaviourNode::Succeed(_) => match self.children[0].tick(context) {
+                BehaviourStatus::Running => BehaviourStatus::Running,
+                _ => BehaviourStatus::Success,
+            },
+            BehaviourNode::Cooldown { seconds, .. } => {
+                if context.time < self.state.ready_at {
+                    return BehaviourStatus::Failure;
+                }
+
+                let status = self.children[0].tick(context);
+                if status == BehaviourStatus::Success {
+                    self.state.ready_at = context.time + seconds;
+                }
+                status
+            }
+            BehaviourNode::IsSet(key) => status(context.blackboard.get(key).is_some()),
+            BehaviourNode::InRange { target, radius } => status(
+                context
+                    .target_offset(target)
+                    .is_some_and(|offset| offset.length() <= *radius),
+            ),
+            BehaviourNode::HealthBelow(fraction) => status(
+                context
+                    .health_fraction
+                    .is_some_and(|health| health < *fraction),
+            ),
+            BehaviourNode::Aware(level) => status(context.awareness >= *level),
+            BehaviourNode::MoveTo { target, range } => {
+                let Some(offset) = context.target_offset(target) else {
+                    return BehaviourStatus::Failure;
+                };
+
+                if offset.length() <= *range {
+                    context.path.clear();
+                    return BehaviourStatus::Success;
+                }
+
+                let destination = context.position + offset;
+                context.walk_towards(destination, &mut self.state, elapsed);
+                BehaviourStatus::Running
+            }
+            BehaviourNode::Attack {
+                target,
+                range,
+                damage,
+                damage_type,
+                duration,
+            } => {
+                if self.state.flag {
+                    return if context.state.is_movement() {
+                        BehaviourStatus::Success
+                    } else {
+                        BehaviourStatus::Running
+                    };
+                }
+
+                let (Some(entity), Some(offset)) = (
+                    context.blackboard.entity(target),
+                    context.target_offset(target),
+                ) else {
+                    return BehaviourStatus::Failure;
+                };
+
+                if offset.length() > *range {
+                    return BehaviourStatus::Failure;
+                }
+
+                if context.state.is_movement() {
+                    context.attack = Some(AttackRequest {
+                        target: entity,
+                        direction: offset.xz(),
+                        damage: *damage,
+                        damage_type: *damage_type,
+                        duration: *duration,
+                    });
+                    self.state.flag = true;
+                }
+
+                BehaviourStatus::Running
+            }
+            BehaviourNode::Shoot { target, range } => {
+                if self.state.flag {
+                    return if context.state.is_movement() {
+                        BehaviourStatus::Success
+                    } else {
+                        BehaviourStatus::Running
+                    };
+                }
+
+                let Some(offset) = context.target_offset(target) else {
+                    return BehaviourStatus::Failure;
+                };
+
+                if !context.can_shoot || offset.length() > *range {
+                    return BehaviourStatus::Failure;
+                }
+
+                if context.state.is_movement() {
+                    context.shot = Some(offset);
+                    self.state.flag = true;
+                }
+
+                BehaviourStatus::Running
+            }
+            BehaviourNode::Wait { seconds } => {
+                if elapsed >= *seconds {
+                    BehaviourStatus::Success
+                } else {
+                    BehaviourStatus::Running
+                }
+            }
+            BehaviourNode::Flee { from, distance } => {
+                let Some(offset) = context.target_offset(from) else {
+                    return BehaviourStatus::Failure;
+                };
+
+                if offset.length() >= *distance {
+                    context.path.clear();
+                    return BehaviourStatus::Success;
+                }
+
+                let away = -offset.normalize_or(Vec3::X);
+                let destination = context.position + offset + away * *distance;
+                context.walk_towards(destination, &mut self.state, elapsed);
+                BehaviourStatus::Running
+            }
+            BehaviourNode::Patrol { route, wait } => {
+                let Some(BlackboardValue::Route(points)) = context.blackboard.get(route) else {
+                    return BehaviourStatus::Failure;
+                };
+                if points.is_empty() {
+                    return BehaviourStatus::Failure;
+                }
+
+                let point = points[self.state.index % points.len()];
+                let offset = (point - context.position).xz();
+
+                if offset.length() > ARRIVE_RADIUS {
+                    // Restart the wait once we arrive at the next point.
+                    self.state.flag = false;
+                    context.walk_towards(point, &mut self.state, elapsed);
+                } else if !self.state.flag {
+                    self.state.flag = true;
+                    self.state.started_at = context.time;
+                    context.path.clear();
+                } else if elapsed >= *wait {
+                    self.state.index = (self.state.index + 1) % points.len();
+                    self.state.flag = false;
+                }
+
+                BehaviourStatus::Running
+            }
+        }
+    }
+}
+
+/// How close a patrolling character needs to get to a patrol point.
+const ARRIVE_RADIUS: f32 = 0.3;
+
+fn status(condition: bool) -> BehaviourStatus {
+    if condition {
+        BehaviourStatus::Success
+    } else {
+        BehaviourStatus::Failure
+    }
+}
+
+/// Everything a node can read or affect during a tick.
+struct BehaviourContext<'a> {
+    tick: u64,
+    time: f32,
+    position: Vec3,
+    state: CharacterState,
+    health_fraction: Option<f32>,
+    awareness: AwarenessLevel,
+    blackboard: &'a Blackboard,
+    path: &'a mut NavPath,
+    tile_map: Option<&'a TileMap>,
+    position_of: &'a dyn Fn(Entity) -> Option<Vec3>,
+    can_shoot: bool,
+
+    // Outputs
+    intent: Vec3,
+    attack: Option<AttackRequest>,
+    /// Direction to fire the character's [`RangedAttack`] in.
+    shot: Option<Vec3>,
+}
+
+impl BehaviourContext<'_> {
+    /// Offset on the ground plane from the character to the target, if it can be resolved.
+    fn target_offset(&self, key: &str) -> Option<Vec3> {
+        let target = match self.blackboard.get(key)? {
+            BlackboardValue::Entity(entity) => (self.position_of)(*entity)?,
+            BlackboardValue::Position(position) => *position,
+            _ => return None,
+        };
+
+        let offset = target - self.position;
+        Some(Vec3::new(offset.x, 0.0, offset.z))
+    }
+
+    /// Sets the intent to follow a path to the destination, repathing periodically.
+    fn walk_towards(&mut self, destination: Vec3, state: &mut NodeState, elapsed: f32) {
+        let repath_count = (elapsed / REPATH_INTERVAL) as usize;
+
+        if (self.path.is_finished() || repath_count != state.repaths)
+            && let Some(tile_map) = self.tile_map
+        {
+            *self.path = find_path(tile_map, self.position, destination)
+                .map(NavPath::new)
+                .unwrap_or_default();
+        }
+        state.repaths = repath_count;
+
+        // Head straight for the destination if there is no path, e.g. while airborne.
+        self.intent = if self.path.is_finished() {
+            let offset = destination - self.position;
+            Vec3::new(offset.x, 0.0, offset.z).normalize_or_zero()
+        } else {
+            self.path.steer(self.position)
+        };
+    }
+}
+
+struct AttackRequest {
+    target: Entity,
+    direction: Vec2,
+    damage: usize,
+    damage_type: DamageType,
+    duration: f32,
+}
+
+pub(super) fn tick_behaviour_trees(
+    time: Res<Time>,
+    definitions: Res<Assets<BehaviourTreeDefinition>>,
+    tile_map: Option<Res<TileMap>>,
+    positions: Query<&WorldPosition>,
+    mut agent_query: Query<(
+        Entity,
+        &mut BehaviourTree,
+        &Blackboard,
+        &mut NavPath,
+        &mut MovementController,
+        &mut Facing,
+        &CharacterState,
+        Option<&Health>,
+        Option<&Awareness>,
+        Option<&RangedAttack>,
+        Option<&Stats>,
+    )>,
+    mut commands: Commands,
+) {
+    let position_of = |entity| positions.get(entity).ok().map(WorldPosition::as_vec3);
+
+    for (
+        entity,
+        mut tree,
+        blackboard,
+        mut path,
+        mut controller,
+        mut facing,
+        state,
+        health,
+        awareness,
+        ranged_attack,
+        stats,
+    ) in &mut agent_query
+    {
+        let Ok(position) = positions.get(entity) else {
+            continue;
+        };
+
+        if tree.root.is_none() {
+            let Some(definition) = definitions.get(&tree.definition) else {
+                continue;
+            };
+            tree.root = Some(NodeRuntime::new(&definition.root));
+        }
+
+        tree.tick += 1;
+        tree.time += time.delta_secs();
+
+        let mut context = BehaviourContext {
+            tick: tree.tick,
+            time: tree.time,
+            position: position.as_vec3(),
+            state: *state,
+            health_fraction: health.map(|health| health.fraction()),
+            awareness: awareness.map_or(AwarenessLevel::Unaware, |awareness| awareness.level),
+            blackboard,
+            path: &mut path,
+            tile_map: tile_map.as_deref(),
+            position_of: &position_of,
+            can_shoot: ranged_attack.is_some(),
+            intent: Vec3::ZERO,
+            attack: None,
+            shot: None,
+        };
+
+        if let Some(root) = tree.root.as_mut() {
+            root.tick(&mut context);
+        }
+
+        let BehaviourContext {
+            intent,
+            attack,
+            shot,
+            ..
+        } = context;
+
+        if !state.is_movement() {
+            controller.intent = Vec3::ZERO;
+            continue;
+        }
+
+        let attack_damage = |damage| stats.map_or(damage, |stats| stats.attack_damage(damage));
+
+        let new_state = if let Some(attack) = attack {
+            controller.intent = Vec3::ZERO;
+            *facing = Facing::from(attack.direction);
+
+            commands.trigger(
+                HealthEvent::new(
+                    attack.target,
+                    HealthEventType::Damage(attack_damage(attack.damage), attack.damage_type),
+                )
+                .with_source(entity),
+            );
+
+            CharacterState::Attacking {
+                time_left: attack.duration,
+                step: 0,
+            }
+        } else if let Some(direction) = shot
+            && let Some(ranged_attack) = ranged_attack
+        {
+            controller.intent = Vec3::ZERO;
+            *facing = Facing::from(direction.xz());
+
+            let mut definition = ranged_attack.projectile.clone();
+            definition.damage = attack_damage(definition.damage);
+
+            commands.trigger(SpawnProjectileEvent {
+                owner: entity,
+                position: position.as_vec3() + direction.normalize_or_zero() * MUZZLE_OFFSET,
+                direction,
+                definition,
+            });
+
+            CharacterState::Attacking {
+                time_left: ranged_attack.duration,
+                step: 0,
+            }
+        } else {
+            controller.intent = intent;
+
+            if intent.length() > 1e-6 {
+                CharacterState::Walking
+            } else {
+                CharacterState::Idle
+            }
+        };
+
+        commands.trigger(CharacterStateEvent::new(entity, new_state));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flees when hurt, otherwise waits near the threat or goes home.
+    fn tree() -> BehaviourTree {
+        let root = BehaviourNode::Selector(vec![
+            BehaviourNode::Sequence(vec![
+                BehaviourNode::HealthBelow(0.3),
+                BehaviourNode::Flee {
+                    from: "threat".to_string(),
+                    distance: 5.0,
+                },
+            ]),
+            BehaviourNode::Sequence(vec![
+                BehaviourNode::InRange {
+                    target: "threat".to_string(),
+                    radius: 3.0,
+                },
+                BehaviourNode::Wait { seconds: 1.0 },
+            ]),
+            BehaviourNode::MoveTo {
+                target: "home".to_string(),
+                range: 0.5,
+            },
+        ]);
+
+        let mut tree = BehaviourTree::new(Handle::default());
+        tree.root = Some(NodeRuntime::new(&root));
+        tree
+    }
+
+    fn blackboard() -> Blackboard {
+        Blackboard::default()
+            .with(
+                "threat",
+                BlackboardValue::Position(Vec3::new(2.0, 0.0, 0.0)),
+            )
+            .with("home", BlackboardValue::Position(Vec3::new(0.0, 0.0, -5.0)))
+    }
+
+    /// Ticks the tree for a character standing at the origin, returning its intent.
+    fn tick(tree: &mut BehaviourTree, blackboard: &Blackboard, health: f32) -> Vec3 {
+        tree.tick += 1;
+        tree.time += 0.1;
+
+        let mut path = NavPath::default();
+        let position_of = |_: Entity| -> Option<Vec3> { None };
+        let mut context = BehaviourContext {
+            tick: tree.tick,
+            time: tree.time,
+            position: Vec3::ZERO,
+            state: CharacterState::Idle,
+            health_fraction: Some(health),
+            awareness: AwarenessLevel::Unaware,
+            blackboard,
+            path: &mut path,
+            tile_map: None,
+            position_of: &position_of,
+            can_shoot: false,
+            intent: Vec3::ZERO,
+            attack: None,
+            shot: None,
+        };
+
+        tree.root.as_mut().unwrap().tick(&mut context);
+        context.intent
+    }
+
+    #[test]
+    fn selector_runs_the_first_branch_that_does_not_fail() {
+        let mut tree = tree();
+
+        let intent = tick(&mut tree, &blackboard(), 1.0);
+
+        assert_eq!(intent, Vec3::ZERO);
+        assert_eq!(tree.active_branch(), "Selector > Sequence > Wait [Running]");
+    }
+
+    #[test]
+    fn selector_falls_through_to_the_last_branch() {
+        let mut tree = tree();
+        let mut blackboard = blackboard();
+        blackboard.remove("threat");
+
+        let intent = tick(&mut tree, &blackboard, 1.0);
+
+        assert_eq!(intent, Vec3::NEG_Z);
+        assert_eq!(tree.active_branch(), "Selector > MoveTo [Running]");
+    }
+
+    #[test]
+    fn higher_priority_branches_interrupt_running_ones() {
+        let mut tree = tree();
+        let blackboard = blackboard();
+
+        tick(&mut tree, &blackboard, 1.0);
+        let intent = tick(&mut tree, &blackboard, 0.1);
+
+        // Straight away from the threat, as there is no tile map to path over.
+        assert_eq!(intent, Vec3::NEG_X);
+        assert_eq!(tree.active_branch(), "Selector > Sequence > Flee [Running]");
+    }
+
+    #[test]
+    fn fleeing_succeeds_once_far_enough_away() {
+        let mut tree = tree();
+        let blackboard = blackboard().with(
+            "threat",
+            BlackboardValue::Position(Vec3::new(6.0, 0.0, 0.0)),
+        );
+
+        let intent = tick(&mut tree, &blackboard, 0.1);
+
+        assert_eq!(intent, Vec3::ZERO);
+        assert_eq!(tree.active_branch(), "Selector > Sequence > Flee [Success]");
+    }
+}
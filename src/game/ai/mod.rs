@@ -0,0 +1,16 @@
+//! Decision making for non-player characters.
+
+use bevy::prelude::*;
+
+pub mod behaviour;
+pub mod perception;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((behaviour::plugin, perception::plugin));
+}
+
+/// Blackboard key holding the player entity while it has the character's attention.
+pub const PLAYER_KEY: &str = "player";
+
+/// Blackboard key holding the position the player was last seen or heard at.
+pub const LAST_KNOWN_POSITION_KEY: &str = "last_known_position";
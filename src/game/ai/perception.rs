@@ -0,0 +1,414 @@
+//! How non-player characters notice the player.
+//!
+//! Characters with [`Perception`] see the player inside a vision cone aligned to their
+//! [`Facing`], as long as no solid tile is in the way, and hear [`NoiseEvent`]s within
+//! their hearing radius. What they perceive raises the suspicion of their [`Awareness`],
+//! which slowly calms down again once the player is out of sight. The result is written
+//! to the [`Blackboard`] so behaviour trees can react to it.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::game::ai::behaviour::{Blackboard, BlackboardValue, tick_behaviour_trees};
+use crate::game::ai::{LAST_KNOWN_POSITION_KEY, PLAYER_KEY};
+use crate::game::character::player::Player;
+use crate::game::character::{Character, CharacterState, CharacterStateEvent, Facing};
+use crate::game::grid::TileMap;
+use crate::game::grid::coords::{TileCoords, WorldPosition};
+use crate::game::grid::tile::TileType;
+use crate::game::physics::components::PhysicsData;
+use crate::game::physics::movement::LandedEvent;
+use crate::screens::Screen;
+use crate::{AppSystems, PausableSystems};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_required_components::<Character, Footsteps>();
+
+    app.add_systems(
+        Update,
+        (
+            emit_footstep_noise.in_set(AppSystems::Update),
+            update_awareness
+                .before(tick_behaviour_trees)
+                .in_set(AppSystems::RecordInput),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+
+    app.add_observer(on_noise)
+        .add_observer(emit_attack_noise)
+        .add_observer(emit_landing_noise);
+}
+
+/// Height of a character's eyes above its [`WorldPosition`].
+const EYE_HEIGHT: f32 = 0.4;
+
+/// Distance between the points checked for solid tiles along a line of sight.
+const SIGHT_STEP: f32 = 0.2;
+
+/// Suspicion gained per second while the player is in plain sight up close.
+const SIGHT_SUSPICION_RATE: f32 = 2.0;
+/// Suspicion gained from hearing a noise of loudness 1.
+const NOISE_SUSPICION: f32 = 0.4;
+/// Suspicion lost per second while nothing is perceived.
+const SUSPICION_DECAY_RATE: f32 = 0.1;
+
+/// Suspicion at which a character becomes suspicious.
+const SUSPICIOUS_THRESHOLD: f32 = 0.3;
+/// Suspicion at which a character becomes alerted.
+const ALERTED_THRESHOLD: f32 = 1.0;
+/// Suspicion below which an alerted character stops being alerted.
+const CALM_DOWN_THRESHOLD: f32 = 0.6;
+/// Suspicion can build up past the alerted threshold, so an alerted character keeps
+/// looking for a while after losing sight of the player.
+const MAX_SUSPICION: f32 = 1.5;
+
+/// Time between the footstep noises of a running character.
+const FOOTSTEP_INTERVAL: f32 = 0.3;
+const FOOTSTEP_LOUDNESS: f32 = 1.0;
+const ATTACK_LOUDNESS: f32 = 0.75;
+/// Loudness of a landing per second spent in the air.
+const LANDING_LOUDNESS_PER_SECOND: f32 = 2.0;
+
+/// The senses of a non-player character.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Perception {
+    /// How far the character can see.
+    pub view_distance: f32,
+    /// Angle between the facing direction and the edge of the vision cone, in radians.
+    pub view_half_angle: f32,
+    /// How far away a noise of loudness 1 can be heard.
+    pub hearing_radius: f32,
+}
+
+impl Default for Perception {
+    fn default() -> Self {
+        Self {
+            view_distance: 6.0,
+            view_half_angle: 60.0_f32.to_radians(),
+            hearing_radius: 4.0,
+        }
+    }
+}
+
+impl Perception {
+    /// Whether the target is inside the vision cone of a character at `position`
+    /// looking in `facing`. Does not check for obstacles.
+    pub fn in_view(&self, position: Vec3, facing: Vec2, target: Vec3) -> bool {
+        let offset = target - position;
+        if offset.length() > self.view_distance {
+            return false;
+        }
+
+        let ground_offset = offset.xz();
+        ground_offset.length() < 1e-3
+            || ground_offset.angle_to(facing).abs() <= self.view_half_angle
+    }
+
+    /// Whether a noise of the given loudness at `noise` can be heard from `position`.
+    pub fn hears(&self, position: Vec3, noise: Vec3, loudness: f32) -> bool {
+        position.distance(noise) <= self.hearing_radius * loudness
+    }
+}
+
+/// Paces the footstep noises of a running character.
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+struct Footsteps {
+    /// Seconds since the last footstep.
+    since_last: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Reflect, Deserialize)]
+pub enum AwarenessLevel {
+    #[default]
+    Unaware,
+    Suspicious,
+    Alerted,
+}
+
+/// How aware a non-player character is of the player.
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct Awareness {
+    pub level: AwarenessLevel,
+    pub suspicion: f32,
+    /// The player the character has noticed.
+    pub target: Option<Entity>,
+    /// Where the target was last seen or heard.
+    pub last_known_position: Option<Vec3>,
+}
+
+impl Awareness {
+    /// Raises suspicion after seeing the target for `delta` seconds. Targets further
+    /// away take longer to notice.
+    pub fn see(&mut self, target: Entity, position: Vec3, distance_fraction: f32, delta: f32) {
+        let closeness = (1.0 - distance_fraction).max(0.25);
+        self.raise(SIGHT_SUSPICION_RATE * closeness * delta);
+        self.target = Some(target);
+        self.last_known_position = Some(position);
+    }
+
+    /// Raises suspicion after hearing a noise made by the target.
+    pub fn hear(&mut self, target: Entity, position: Vec3, loudness: f32) {
+        self.raise(NOISE_SUSPICION * loudness);
+        self.target = Some(target);
+        self.last_known_position = Some(position);
+    }
+
+    /// Lowers suspicion after `delta` seconds of perceiving nothing.
+    pub fn calm_down(&mut self, delta: f32) {
+        self.suspicion = (self.suspicion - SUSPICION_DECAY_RATE * delta).max(0.0);
+        self.update_level();
+
+        if self.level == AwarenessLevel::Unaware {
+            self.target = None;
+            self.last_known_position = None;
+        }
+    }
+
+    fn raise(&mut self, amount: f32) {
+        self.suspicion = (self.suspicion + amount).min(MAX_SUSPICION);
+        self.update_level();
+    }
+
+    fn update_level(&mut self) {
+        self.level = match self.level {
+            AwarenessLevel::Alerted if self.suspicion >= CALM_DOWN_THRESHOLD => {
+                AwarenessLevel::Alerted
+            }
+            _ if self.suspicion >= ALERTED_THRESHOLD => AwarenessLevel::Alerted,
+            _ if self.suspicion >= SUSPICIOUS_THRESHOLD => AwarenessLevel::Suspicious,
+            _ => AwarenessLevel::Unaware,
+        };
+    }
+}
+
+/// Triggered when something makes a sound that characters with [`Perception`] may hear.
+#[derive(Event, Debug, Clone)]
+pub struct NoiseEvent {
+    pub source: Entity,
+    pub position: Vec3,
+    /// Multiplier for the hearing radius of listeners.
+    pub loudness: f32,
+}
+
+/// Whether nothing solid blocks the straight line between two points.
+pub fn has_line_of_sight(tile_map: &TileMap, from: Vec3, to: Vec3) -> bool {
+    let steps = (from.distance(to) / SIGHT_STEP).ceil() as usize;
+
+    (1..steps).all(|step| {
+        let point = from.lerp(to, step as f32 / steps as f32);
+        let coords = TileCoords(point.round().as_ivec3());
+
+        !matches!(
+            tile_map.get(&coords),
+            Some(TileType::Full { .. } | TileType::Layer { .. })
+        )
+    })
+}
+
+fn update_awareness(
+    time: Res<Time>,
+    tile_map: Option<Res<TileMap>>,
+    player_query: Query<(Entity, &WorldPosition), With<Player>>,
+    mut npc_query: Query<(
+        &Perception,
+        &mut Awareness,
+        &mut Blackboard,
+        &WorldPosition,
+        &Facing,
+    )>,
+) {
+    for (perception, mut awareness, mut blackboard, position, facing) in &mut npc_query {
+        let eyes = position.as_vec3() + Vec3::Y * EYE_HEIGHT;
+
+        let seen = player_query
+            .iter()
+            .map(|(player, player_position)| {
+                (player, player_position.as_vec3() + Vec3::Y * EYE_HEIGHT)
+            })
+            .filter(|(_, target)| {
+                perception.in_view(eyes, facing.direction(), *target)
+                    && tile_map
+                        .as_deref()
+                        .is_none_or(|tile_map| has_line_of_sight(tile_map, eyes, *target))
+            })
+            .min_by(|(_, a), (_, b)| eyes.distance(*a).total_cmp(&eyes.distance(*b)));
+
+        match seen {
+            Some((player, target)) => awareness.see(
+                player,
+                target - Vec3::Y * EYE_HEIGHT,
+                eyes.distance(target) / perception.view_distance,
+                time.delta_secs(),
+            ),
+            None => awareness.calm_down(time.delta_secs()),
+        }
+
+        match awareness.target {
+            Some(target) if awareness.level == AwarenessLevel::Alerted => {
+                blackboard.set(PLAYER_KEY, BlackboardValue::Entity(target))
+            }
+            _ => blackboard.remove(PLAYER_KEY),
+        }
+
+        match awareness.last_known_position {
+            Some(position) => {
+                blackboard.set(LAST_KNOWN_POSITION_KEY, BlackboardValue::Position(position))
+            }
+            None => blackboard.remove(LAST_KNOWN_POSITION_KEY),
+        }
+    }
+}
+
+fn on_noise(
+    event: On<NoiseEvent>,
+    player_query: Query<(), With<Player>>,
+    mut npc_query: Query<(&Perception, &mut Awareness, &WorldPosition)>,
+) {
+    // Only the player is worth paying attention to for now.
+    if !player_query.contains(event.source) {
+        return;
+    }
+
+    for (perception, mut awareness, position) in &mut npc_query {
+        if perception.hears(position.as_vec3(), event.position, event.loudness) {
+            awareness.hear(event.source, event.position, event.loudness);
+        }
+    }
+}
+
+fn emit_footstep_noise(
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &mut Footsteps,
+        &CharacterState,
+        &PhysicsData,
+        &WorldPosition,
+    )>,
+    mut commands: Commands,
+) {
+    for (entity, mut footsteps, state, physics, position) in &mut query {
+        footsteps.since_last += time.delta_secs();
+
+        let grounded = matches!(physics, PhysicsData::Kinematic { grounded: true, .. });
+
+        if *state == CharacterState::Running
+            && grounded
+            && footsteps.since_last >= FOOTSTEP_INTERVAL
+        {
+            footsteps.since_last = 0.0;
+            commands.trigger(NoiseEvent {
+                source: entity,
+                position: position.as_vec3(),
+                loudness: FOOTSTEP_LOUDNESS,
+            });
+        }
+    }
+}
+
+fn emit_attack_noise(
+    event: On<CharacterStateEvent>,
+    query: Query<&WorldPosition>,
+    mut commands: Commands,
+) {
+    if !matches!(
+        event.new_state(),
+        CharacterState::Attacking { .. } | CharacterState::Throwing { .. }
+    ) {
+        return;
+    }
+
+    if let Ok(position) = query.get(event.event_target()) {
+        commands.trigger(NoiseEvent {
+            source: event.event_target(),
+            position: position.as_vec3(),
+            loudness: ATTACK_LOUDNESS,
+        });
+    }
+}
+
+fn emit_landing_noise(
+    event: On<LandedEvent>,
+    query: Query<&WorldPosition>,
+    mut commands: Commands,
+) {
+    if let Ok(position) = query.get(event.event_target()) {
+        commands.trigger(NoiseEvent {
+            source: event.event_target(),
+            position: position.as_vec3(),
+            loudness: event.air_time * LANDING_LOUDNESS_PER_SECOND,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::grid::tile::TileFacing;
+
+    fn perception() -> Perception {
+        Perception {
+            view_distance: 6.0,
+            view_half_angle: 45.0_f32.to_radians(),
+            hearing_radius: 4.0,
+        }
+    }
+
+    #[test]
+    fn targets_are_seen_inside_the_vision_cone() {
+        let perception = perception();
+
+        assert!(perception.in_view(Vec3::ZERO, Vec2::X, Vec3::new(3.0, 0.0, 1.0)));
+        assert!(perception.in_view(Vec3::ZERO, Vec2::X, Vec3::new(3.0, 0.0, -1.0)));
+        // Standing right on top of the character.
+        assert!(perception.in_view(Vec3::ZERO, Vec2::X, Vec3::Y * 0.5));
+    }
+
+    #[test]
+    fn targets_outside_the_vision_cone_are_not_seen() {
+        let perception = perception();
+
+        // Too far to the side, behind, and too far away.
+        assert!(!perception.in_view(Vec3::ZERO, Vec2::X, Vec3::new(1.0, 0.0, 3.0)));
+        assert!(!perception.in_view(Vec3::ZERO, Vec2::X, Vec3::new(-3.0, 0.0, 0.0)));
+        assert!(!perception.in_view(Vec3::ZERO, Vec2::X, Vec3::new(7.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn noises_are_heard_within_a_radius_scaled_by_loudness() {
+        let perception = perception();
+        let noise = Vec3::new(3.0, 0.0, 0.0);
+
+        assert!(perception.hears(Vec3::ZERO, noise, 1.0));
+        assert!(!perception.hears(Vec3::ZERO, noise, 0.5));
+        assert!(perception.hears(Vec3::ZERO, Vec3::new(6.0, 0.0, 0.0), 2.0));
+    }
+
+    #[test]
+    fn solid_tiles_block_the_line_of_sight() {
+        let from = Vec3::new(0.0, 1.0, 0.0);
+        let to = Vec3::new(4.0, 1.0, 0.0);
+
+        let mut tile_map = TileMap::default();
+        assert!(has_line_of_sight(&tile_map, from, to));
+
+        tile_map.insert(IVec3::new(2, 1, 0), TileType::default());
+        assert!(!has_line_of_sight(&tile_map, from, to));
+    }
+
+    #[test]
+    fn only_solid_tiles_block_the_line_of_sight() {
+        let from = Vec3::new(0.0, 1.0, 0.0);
+        let to = Vec3::new(4.0, 1.0, 0.0);
+
+        let mut tile_map = TileMap::default();
+        tile_map.insert(IVec3::new(2, 1, 0), TileType::Stairs(TileFacing::PosX));
+
+        assert!(has_line_of_sight(&tile_map, from, to));
+    }
+}
@@ -1,9 +1,9 @@
 use crate::game::character::{CharacterState, Facing};
+use crate::screens::Screen;
 use crate::{AppSystems, PausableSystems};
 use bevy::prelude::*;
 use std::fmt::Debug;
 use std::time::Duration;
-use crate::screens::Screen;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
@@ -30,7 +30,11 @@ fn update_animation_state(query: Query<(&mut CharacterAnimation, &CharacterState
         animation.facing = *facing;
 
         match state {
-            CharacterState::Idle => {
+            // There is no throwing animation yet.
+            CharacterState::Idle
+            | CharacterState::Throwing { .. }
+            | CharacterState::Blocking { .. }
+            | CharacterState::Staggered { .. } => {
                 animation.set_idle();
             }
             CharacterState::Walking => {
@@ -43,9 +47,14 @@ fn update_animation_state(query: Query<(&mut CharacterAnimation, &CharacterState
                     .set_running()
                     .unwrap_or_else(|_| animation.set_idle());
             }
-            CharacterState::Attacking { .. } => {
+            CharacterState::Attacking { step, .. } => {
+                animation
+                    .set_attacking(*step)
+                    .unwrap_or_else(|_| animation.set_idle());
+            }
+            CharacterState::Dodging { .. } => {
                 animation
-                    .set_attacking()
+                    .set_dodging()
                     .unwrap_or_else(|_| animation.set_idle());
             }
         }
@@ -138,23 +147,42 @@ impl CharacterAnimation {
         Ok(())
     }
 
-    fn set_attacking(&mut self) -> Result<(), AnimationError> {
-        if matches!(self.state, AnimationState::Attacking) {
+    fn set_attacking(&mut self, step: usize) -> Result<(), AnimationError> {
+        if matches!(self.state, AnimationState::Attacking(current) if current == step) {
             return Ok(());
         }
 
         let attack = self
             .capabilities
             .attack
-            .as_ref()
-            .ok_or(AnimationError::NoSuchCapability(AnimationState::Attacking))?;
+            .get(step)
+            .ok_or(AnimationError::NoSuchCapability(AnimationState::Attacking(
+                step,
+            )))?;
 
-        self.state = AnimationState::Attacking;
+        self.state = AnimationState::Attacking(step);
         self.timer = Timer::new(attack.interval, TimerMode::Repeating);
         self.frame = 0;
         Ok(())
     }
 
+    fn set_dodging(&mut self) -> Result<(), AnimationError> {
+        if matches!(self.state, AnimationState::Dodging) {
+            return Ok(());
+        }
+
+        let dodge = self
+            .capabilities
+            .dodge
+            .as_ref()
+            .ok_or(AnimationError::NoSuchCapability(AnimationState::Dodging))?;
+
+        self.state = AnimationState::Dodging;
+        self.timer = Timer::new(dodge.interval, TimerMode::Repeating);
+        self.frame = 0;
+        Ok(())
+    }
+
     fn reset(&mut self) {
         self.state = AnimationState::Idling;
         self.timer = Timer::new(self.capabilities.idle.interval, TimerMode::Repeating);
@@ -189,8 +217,8 @@ impl CharacterAnimation {
                         return;
                     }
                 }
-                AnimationState::Attacking => {
-                    if let Some(attack) = &self.capabilities.attack {
+                AnimationState::Attacking(step) => {
+                    if let Some(attack) = self.capabilities.attack.get(step) {
                         attack.frames
                     } else {
                         // If we somehow got into an invalid state, reset the animation to idle
@@ -198,6 +226,15 @@ impl CharacterAnimation {
                         return;
                     }
                 }
+                AnimationState::Dodging => {
+                    if let Some(dodge) = &self.capabilities.dodge {
+                        dodge.frames
+                    } else {
+                        // If we somehow got into an invalid state, reset the animation to idle
+                        self.reset();
+                        return;
+                    }
+                }
             };
     }
 
@@ -220,13 +257,20 @@ impl CharacterAnimation {
                     default
                 }
             }
-            AnimationState::Attacking => {
-                if let Some(attack) = &self.capabilities.attack {
+            AnimationState::Attacking(step) => {
+                if let Some(attack) = self.capabilities.attack.get(step) {
                     &attack.image
                 } else {
                     default
                 }
             }
+            AnimationState::Dodging => {
+                if let Some(dodge) = &self.capabilities.dodge {
+                    &dodge.image
+                } else {
+                    default
+                }
+            }
         }
     }
 
@@ -249,13 +293,20 @@ impl CharacterAnimation {
                     default
                 }
             }
-            AnimationState::Attacking => {
-                if let Some(attack) = &self.capabilities.attack {
+            AnimationState::Attacking(step) => {
+                if let Some(attack) = self.capabilities.attack.get(step) {
                     &attack.atlas
                 } else {
                     default
                 }
             }
+            AnimationState::Dodging => {
+                if let Some(dodge) = &self.capabilities.dodge {
+                    &dodge.atlas
+                } else {
+                    default
+                }
+            }
         }
     }
 
@@ -283,14 +334,22 @@ impl CharacterAnimation {
                     default
                 }
             }
-            AnimationState::Attacking => {
-                if let Some(attack) = &self.capabilities.attack {
+            AnimationState::Attacking(step) => {
+                if let Some(attack) = self.capabilities.attack.get(step) {
                     let offset = self.facing as usize * attack.frames;
                     self.frame + offset
                 } else {
                     default
                 }
             }
+            AnimationState::Dodging => {
+                if let Some(dodge) = &self.capabilities.dodge {
+                    let offset = self.facing as usize * dodge.frames;
+                    self.frame + offset
+                } else {
+                    default
+                }
+            }
         }
     }
 
@@ -313,7 +372,9 @@ pub struct AnimationCapabilities {
     pub idle: CharacterAnimationData,
     pub walk: Option<CharacterAnimationData>,
     pub run: Option<CharacterAnimationData>,
-    pub attack: Option<CharacterAnimationData>,
+    /// One animation per step of the character's attack combo.
+    pub attack: Vec<CharacterAnimationData>,
+    pub dodge: Option<CharacterAnimationData>,
 }
 
 #[derive(Debug, Clone, Reflect)]
@@ -321,5 +382,6 @@ pub enum AnimationState {
     Idling,
     Walking,
     Running,
-    Attacking,
+    Attacking(usize),
+    Dodging,
 }
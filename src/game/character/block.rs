@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+
+use crate::game::character::CharacterState;
+
+/// How long after raising a block a hit is parried instead of blocked, in seconds.
+pub const PARRY_WINDOW: f32 = 0.2;
+/// How long a character is staggered by a parry or a broken guard, in seconds.
+pub const STAGGER_DURATION: f32 = 0.8;
+/// Fraction of the damage of a blocked hit that still lands.
+const BLOCKED_DAMAGE_FRACTION: f32 = 0.25;
+/// Cosine of the half-angle of the arc in front of a character that a block covers.
+const BLOCK_ARC_COS: f32 = 0.0;
+pub const BLOCK_STAMINA_COST: usize = 25;
+
+/// What happens when a hit reaches a character who may be blocking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockOutcome {
+    /// The hit lands in full.
+    Hit,
+    /// The hit is blocked and only the given amount of damage lands.
+    Blocked(usize),
+    /// The character has no stamina left to keep blocking, so the guard breaks, the hit
+    /// lands in full and the character is staggered.
+    GuardBroken,
+    /// The hit is deflected entirely and the attacker is staggered.
+    Parried,
+}
+
+/// Works out how a character in `state` facing `facing` handles a hit of `damage` coming
+/// from `direction`, the direction on the xz plane from the character to the attacker.
+pub fn resolve_block(
+    state: &CharacterState,
+    facing: Vec2,
+    direction: Vec2,
+    damage: usize,
+    stamina: usize,
+) -> BlockOutcome {
+    let CharacterState::Blocking { elapsed } = state else {
+        return BlockOutcome::Hit;
+    };
+
+    // Hits from behind or the sides can not be blocked.
+    if direction.normalize_or_zero().dot(facing) < BLOCK_ARC_COS {
+        return BlockOutcome::Hit;
+    }
+
+    if *elapsed <= PARRY_WINDOW {
+        BlockOutcome::Parried
+    } else if stamina < BLOCK_STAMINA_COST {
+        BlockOutcome::GuardBroken
+    } else {
+        BlockOutcome::Blocked((damage as f32 * BLOCKED_DAMAGE_FRACTION).round() as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FACING: Vec2 = Vec2::X;
+
+    fn blocking(elapsed: f32) -> CharacterState {
+        CharacterState::Blocking { elapsed }
+    }
+
+    #[test]
+    fn hits_land_in_full_without_blocking() {
+        assert_eq!(
+            resolve_block(&CharacterState::Idle, FACING, Vec2::X, 20, 100),
+            BlockOutcome::Hit
+        );
+    }
+
+    #[test]
+    fn hits_early_in_a_block_are_parried() {
+        assert_eq!(
+            resolve_block(&blocking(0.0), FACING, Vec2::X, 20, 100),
+            BlockOutcome::Parried
+        );
+        assert_eq!(
+            resolve_block(&blocking(PARRY_WINDOW), FACING, Vec2::X, 20, 100),
+            BlockOutcome::Parried
+        );
+    }
+
+    #[test]
+    fn hits_after_the_parry_window_are_blocked() {
+        assert_eq!(
+            resolve_block(&blocking(PARRY_WINDOW + 0.1), FACING, Vec2::X, 20, 100),
+            BlockOutcome::Blocked(5)
+        );
+    }
+
+    #[test]
+    fn blocking_needs_enough_stamina_to_pay_for_it() {
+        let state = blocking(1.0);
+
+        assert_eq!(
+            resolve_block(&state, FACING, Vec2::X, 20, BLOCK_STAMINA_COST),
+            BlockOutcome::Blocked(5)
+        );
+        assert_eq!(
+            resolve_block(&state, FACING, Vec2::X, 20, BLOCK_STAMINA_COST - 1),
+            BlockOutcome::GuardBroken
+        );
+    }
+
+    #[test]
+    fn hits_from_behind_are_not_blocked() {
+        assert_eq!(
+            resolve_block(&blocking(0.0), FACING, Vec2::NEG_X, 20, 100),
+            BlockOutcome::Hit
+        );
+        assert_eq!(
+            resolve_block(&blocking(1.0), FACING, Vec2::new(-1.0, 0.5), 20, 100),
+            BlockOutcome::Hit
+        );
+    }
+}
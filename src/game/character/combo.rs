@@ -0,0 +1,336 @@
+//! Attack combos, where pressing attack again near the end of a swing chains into the
+//! next swing of the combo.
+//!
+//! A combo describes the shape of each swing, while the damage, damage type, speed and
+//! stamina cost come from the [`WeaponStats`] the combo is performed with. The swings are
+//! loaded from `.combo.ron` asset files into a [`ComboDefinition`].
+
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::asset_tracking::RegisterRonAsset;
+use crate::game::character::CharacterState;
+use crate::game::character::animation::CharacterAnimationData;
+use crate::game::item::equipment::WeaponStats;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ron_asset::<ComboDefinition>(&["combo.ron"]);
+}
+
+/// How long before the cancel window opens an attack input is remembered, in seconds.
+const INPUT_BUFFER: f32 = 0.15;
+
+/// Sprite sheets of swings have one row per [`Facing`](crate::game::character::Facing).
+const FACING_ROWS: u32 = 8;
+
+/// The swings of a combo, as written in an asset file.
+#[derive(Asset, TypePath, Debug, Clone, Default, Deserialize)]
+pub struct ComboDefinition {
+    pub steps: Vec<ComboStepDefinition>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ComboStepDefinition {
+    pub damage_scale: f32,
+    pub stamina_scale: f32,
+    pub duration: f32,
+    pub cancel_window: f32,
+    pub range: f32,
+    pub arc_cos: f32,
+    /// What the character plays during the swing.
+    pub animation: SwingSheet,
+    #[serde(default)]
+    pub particle: Option<SwingSheet>,
+}
+
+/// A run of frames in a sprite sheet with one row per facing, e.g.
+/// `(image: "images/characters/attack.png", frame_size: (96, 96), first_frame: 3, frames: 4)`.
+/// Swings can share a sheet by playing different parts of it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SwingSheet {
+    /// Asset path of the sheet.
+    pub image: String,
+    pub frame_size: UVec2,
+    /// The column the swing starts at.
+    #[serde(default)]
+    pub first_frame: u32,
+    pub frames: u32,
+    /// Size of the sprite relative to a frame, e.g. to make a finisher's slash bigger.
+    #[serde(default = "default_sheet_scale")]
+    pub scale: f32,
+}
+
+fn default_sheet_scale() -> f32 {
+    1.0
+}
+
+impl SwingSheet {
+    /// Only the swing's frames, so frame `n` facing `f` is at index `f * frames + n`.
+    fn layout(&self) -> TextureAtlasLayout {
+        TextureAtlasLayout::from_grid(
+            self.frame_size,
+            self.frames,
+            FACING_ROWS,
+            None,
+            Some(UVec2::new(self.first_frame * self.frame_size.x, 0)),
+        )
+    }
+}
+
+impl ComboDefinition {
+    /// The steps of a [`Combo`] and the attack animation of each, in order.
+    pub fn build(
+        &self,
+        asset_server: &AssetServer,
+        texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+    ) -> (Vec<ComboStep>, Vec<CharacterAnimationData>) {
+        self.steps
+            .iter()
+            .map(|step| {
+                let animation = CharacterAnimationData {
+                    image: asset_server.load(&step.animation.image),
+                    atlas: TextureAtlas {
+                        layout: texture_atlas_layouts.add(step.animation.layout()),
+                        index: 0,
+                    },
+                    frames: step.animation.frames as usize,
+                    interval: Duration::from_secs_f32(
+                        step.duration / step.animation.frames.max(1) as f32,
+                    ),
+                };
+
+                let particle = step.particle.as_ref().map(|particle| ComboParticle {
+                    image: asset_server.load(&particle.image),
+                    layout: texture_atlas_layouts.add(particle.layout()),
+                    frames: particle.frames as usize,
+                    size: particle.frame_size.as_vec2() * particle.scale,
+                });
+
+                let step = ComboStep {
+                    damage_scale: step.damage_scale,
+                    stamina_scale: step.stamina_scale,
+                    duration: step.duration,
+                    cancel_window: step.cancel_window,
+                    range: step.range,
+                    arc_cos: step.arc_cos,
+                    particle,
+                };
+
+                (step, animation)
+            })
+            .unzip()
+    }
+}
+
+/// The swings of a character's attack combo, in order. The animation of each step is the
+/// attack animation with the same index in the character's
+/// [`AnimationCapabilities`](crate::game::character::animation::AnimationCapabilities).
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Combo {
+    pub steps: Vec<ComboStep>,
+    /// The equipped weapon, or the character's bare hands.
+    pub weapon: WeaponStats,
+    unarmed: WeaponStats,
+    /// Whether the next step has been requested during the current one.
+    queued: bool,
+}
+
+#[derive(Debug, Clone, Reflect)]
+pub struct ComboStep {
+    /// Multiplier on the weapon's damage.
+    pub damage_scale: f32,
+    /// Multiplier on the weapon's stamina cost.
+    pub stamina_scale: f32,
+    /// Length of the swing in seconds, at a swing speed of 1.
+    pub duration: f32,
+    /// The last part of the swing, in seconds at a swing speed of 1, during which it can
+    /// be cancelled into the next step.
+    pub cancel_window: f32,
+    pub range: f32,
+    /// Cosine of the half-angle of the arc in front of the character that the swing hits.
+    pub arc_cos: f32,
+    pub particle: Option<ComboParticle>,
+}
+
+/// A particle spawned on the character for a swing. The layout has one row per
+/// [`Facing`](crate::game::character::Facing).
+#[derive(Debug, Clone, Reflect)]
+pub struct ComboParticle {
+    pub image: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+    pub frames: usize,
+    pub size: Vec2,
+}
+
+impl ComboStep {
+    pub fn damage(&self, weapon: &WeaponStats) -> usize {
+        (weapon.damage as f32 * self.damage_scale).round() as usize
+    }
+
+    pub fn stamina_cost(&self, weapon: &WeaponStats) -> usize {
+        (weapon.stamina_cost as f32 * self.stamina_scale).round() as usize
+    }
+
+    pub fn duration(&self, weapon: &WeaponStats) -> f32 {
+        self.duration / weapon.swing_speed()
+    }
+
+    pub fn cancel_window(&self, weapon: &WeaponStats) -> f32 {
+        self.cancel_window / weapon.swing_speed()
+    }
+}
+
+impl Combo {
+    /// A combo performed with `unarmed` while no weapon is equipped.
+    pub fn new(steps: Vec<ComboStep>, unarmed: WeaponStats) -> Self {
+        Self {
+            steps,
+            weapon: unarmed.clone(),
+            unarmed,
+            queued: false,
+        }
+    }
+
+    /// Performs the combo with the weapon, or unarmed if there is none.
+    pub fn set_weapon(&mut self, weapon: Option<WeaponStats>) {
+        self.weapon = weapon.unwrap_or_else(|| self.unarmed.clone());
+    }
+
+    /// Handles an attack input. Returns the step to start right away, if any, or queues
+    /// the next step if the current swing will soon be cancellable.
+    pub fn request_attack(&mut self, state: &CharacterState) -> Option<usize> {
+        match *state {
+            _ if state.is_movement() => {
+                self.queued = false;
+                (!self.steps.is_empty()).then_some(0)
+            }
+            CharacterState::Attacking { time_left, step } => {
+                if let Some(current) = self.steps.get(step)
+                    && step + 1 < self.steps.len()
+                    && time_left <= current.cancel_window(&self.weapon) + INPUT_BUFFER
+                {
+                    self.queued = true;
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the queued step once the current swing can be cancelled into it.
+    pub fn take_queued(&mut self, state: &CharacterState) -> Option<usize> {
+        let CharacterState::Attacking { time_left, step } = *state else {
+            // The combo was interrupted or the swing finished without chaining.
+            self.queued = false;
+            return None;
+        };
+
+        let cancellable = self
+            .steps
+            .get(step)
+            .is_some_and(|current| time_left <= current.cancel_window(&self.weapon));
+
+        if self.queued && cancellable {
+            self.queued = false;
+            Some(step + 1)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::character::health::DamageType;
+
+    fn combo(steps: usize) -> Combo {
+        let step = ComboStep {
+            damage_scale: 1.0,
+            stamina_scale: 1.0,
+            duration: 0.5,
+            cancel_window: 0.2,
+            range: 1.0,
+            arc_cos: 0.5,
+            particle: None,
+        };
+        let unarmed = WeaponStats {
+            damage: 10,
+            damage_type: DamageType::Blunt,
+            swing_speed: 1.0,
+            stamina_cost: 10,
+        };
+
+        Combo::new(vec![step; steps], unarmed)
+    }
+
+    #[test]
+    fn attacking_mid_throw_does_not_queue_a_step() {
+        let mut combo = combo(3);
+        let throwing = CharacterState::Throwing { time_left: 0.1 };
+
+        assert_eq!(combo.request_attack(&throwing), None);
+        assert_eq!(combo.take_queued(&throwing), None);
+        assert_eq!(combo.request_attack(&CharacterState::Idle), Some(0));
+    }
+
+    fn attacking(step: usize, time_left: f32) -> CharacterState {
+        CharacterState::Attacking { time_left, step }
+    }
+
+    #[test]
+    fn attacking_from_movement_starts_the_first_step() {
+        assert_eq!(combo(3).request_attack(&CharacterState::Running), Some(0));
+        assert_eq!(combo(0).request_attack(&CharacterState::Idle), None);
+    }
+
+    #[test]
+    fn attacks_are_only_queued_near_the_cancel_window() {
+        let mut combo = combo(3);
+
+        // Too early in the swing, so the press is dropped.
+        assert_eq!(combo.request_attack(&attacking(0, 0.45)), None);
+        assert_eq!(combo.take_queued(&attacking(0, 0.1)), None);
+
+        // Within the input buffer, but the swing can't be cancelled yet.
+        assert_eq!(combo.request_attack(&attacking(0, 0.3)), None);
+        assert_eq!(combo.take_queued(&attacking(0, 0.3)), None);
+        assert_eq!(combo.take_queued(&attacking(0, 0.2)), Some(1));
+    }
+
+    #[test]
+    fn queued_steps_chain_to_the_end_of_the_combo() {
+        let mut combo = combo(2);
+
+        combo.request_attack(&attacking(0, 0.1));
+        assert_eq!(combo.take_queued(&attacking(0, 0.1)), Some(1));
+
+        // There is no step after the last one.
+        combo.request_attack(&attacking(1, 0.1));
+        assert_eq!(combo.take_queued(&attacking(1, 0.1)), None);
+    }
+
+    #[test]
+    fn interrupting_the_swing_drops_the_queued_step() {
+        let mut combo = combo(3);
+
+        combo.request_attack(&attacking(0, 0.3));
+        assert_eq!(
+            combo.take_queued(&CharacterState::Staggered { time_left: 0.5 }),
+            None
+        );
+        assert_eq!(combo.take_queued(&attacking(0, 0.1)), None);
+    }
+
+    #[test]
+    fn one_press_starts_one_step() {
+        let mut combo = combo(3);
+
+        combo.request_attack(&attacking(0, 0.1));
+        assert_eq!(combo.take_queued(&attacking(0, 0.1)), Some(1));
+        assert_eq!(combo.take_queued(&attacking(0, 0.05)), None);
+        assert_eq!(combo.take_queued(&attacking(1, 0.1)), None);
+    }
+}
@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+
+use crate::game::character::stamina::{Stamina, StaminaEvent, StaminaEventType};
+use crate::game::character::{CharacterState, CharacterStateEvent, Facing};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(on_dodge);
+}
+
+/// How long a dodge lasts, in seconds.
+pub const DODGE_DURATION: f32 = 0.4;
+/// The last part of a dodge, in seconds, during which the character can be hit again
+/// and may start another dodge.
+pub const DODGE_RECOVERY: f32 = 0.1;
+/// Speed of a dodge in world units per second.
+const DODGE_SPEED: f32 = 7.0;
+const DODGE_STAMINA_COST: usize = 40;
+
+/// Makes a character dodge in the given direction on the xz plane. Falls back to the
+/// direction the character is facing if the direction is zero.
+#[derive(EntityEvent, Debug, Clone, Reflect)]
+pub struct DodgeEvent {
+    entity: Entity,
+    direction: Vec2,
+}
+
+impl DodgeEvent {
+    pub fn new(entity: Entity, direction: Vec2) -> Self {
+        Self { entity, direction }
+    }
+}
+
+fn on_dodge(
+    event: On<DodgeEvent>,
+    mut query: Query<(&CharacterState, &mut Facing, &Stamina)>,
+    mut commands: Commands,
+) {
+    let Ok((state, mut facing, stamina)) = query.get_mut(event.entity) else {
+        return;
+    };
+
+    let can_dodge = match state {
+        CharacterState::Dodging { time_left, .. } => *time_left <= DODGE_RECOVERY,
+        state => state.is_movement(),
+    };
+
+    // Dodges can only be chained for as long as there is stamina left to pay for them.
+    if !can_dodge || stamina.current() < DODGE_STAMINA_COST {
+        return;
+    }
+
+    let direction = event
+        .direction
+        .try_normalize()
+        .unwrap_or_else(|| facing.direction());
+
+    *facing = Facing::from(direction);
+
+    commands.trigger(StaminaEvent::new(
+        event.entity,
+        StaminaEventType::Spend(DODGE_STAMINA_COST),
+    ));
+    commands.trigger(CharacterStateEvent::new(
+        event.entity,
+        CharacterState::Dodging {
+            time_left: DODGE_DURATION,
+            velocity: Vec3::new(direction.x, 0.0, direction.y) * DODGE_SPEED,
+        },
+    ));
+}
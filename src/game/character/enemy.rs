@@ -0,0 +1,251 @@
+//! Enemy-specific behavior.
+
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::asset_tracking::LoadResource;
+use crate::game::ai::behaviour::{
+    BehaviourTree, BehaviourTreeDefinition, Blackboard, BlackboardValue,
+};
+use crate::game::ai::perception::{Awareness, Perception};
+use crate::game::character::animation::{
+    AnimationCapabilities, CharacterAnimation, CharacterAnimationData,
+};
+use crate::game::character::character;
+use crate::game::character::health::{DamageType, DeathEvent, Health, Resistances};
+use crate::game::character::stats::{Stat, Stats};
+use crate::game::grid::pathfinding::NavPath;
+use crate::game::item::equipment::{Equipment, EquipmentSlot};
+use crate::game::loot::{DropLootEvent, Loot};
+use crate::game::physics::components::Collider;
+use crate::game::physics::movement::MovementController;
+use crate::game::projectile::{
+    BOLT_FRAMES, ProjectileAssets, ProjectileDefinition, ProjectileSprite, RangedAttack,
+    TerrainImpact,
+};
+use crate::game::quest::QuestTarget;
+
+pub(super) fn plugin(app: &mut App) {
+    app.load_resource::<EnemyAssets>();
+
+    app.add_observer(on_enemy_death);
+}
+
+/// A basic melee enemy which patrols between the given points and chases the player.
+pub fn enemy(
+    position: Vec3,
+    patrol_route: Vec<Vec3>,
+    max_speed: f32,
+    enemy_assets: &EnemyAssets,
+    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+    scale: f32,
+) -> impl Bundle {
+    (
+        enemy_base(
+            "Enemy",
+            position,
+            patrol_route,
+            max_speed,
+            enemy_assets.behaviour.clone(),
+            ENEMY_TINT,
+            enemy_assets,
+            texture_atlas_layouts,
+            scale,
+        ),
+        Loot::new("enemy"),
+        QuestTarget::new("enemy"),
+    )
+}
+
+/// An enemy which keeps its distance from the player and throws fire bolts at them.
+pub fn ranged_enemy(
+    position: Vec3,
+    patrol_route: Vec<Vec3>,
+    max_speed: f32,
+    enemy_assets: &EnemyAssets,
+    projectile_assets: &ProjectileAssets,
+    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+    scale: f32,
+) -> impl Bundle {
+    (
+        enemy_base(
+            "Ranged Enemy",
+            position,
+            patrol_route,
+            max_speed,
+            enemy_assets.ranged_behaviour.clone(),
+            RANGED_ENEMY_TINT,
+            enemy_assets,
+            texture_atlas_layouts,
+            scale,
+        ),
+        Loot::new("ranged_enemy"),
+        QuestTarget::new("ranged_enemy"),
+        RangedAttack {
+            projectile: fire_bolt(projectile_assets),
+            duration: ENEMY_ATTACK_DURATION as f32 / 1000.0,
+        },
+    )
+}
+
+/// A fast, straight flying bolt which burns whatever it hits.
+fn fire_bolt(projectile_assets: &ProjectileAssets) -> ProjectileDefinition {
+    ProjectileDefinition {
+        speed: 6.0,
+        gravity: 0.0,
+        lifetime: 2.0,
+        damage: 15,
+        damage_type: DamageType::Fire,
+        on_terrain: TerrainImpact::Despawn,
+        sprite: ProjectileSprite {
+            image: projectile_assets.bolt.clone(),
+            layout: projectile_assets.bolt_layout.clone(),
+            frames: BOLT_FRAMES,
+            interval: Duration::from_millis(50),
+            angle: 0.0,
+            scale: 0.3,
+        },
+    }
+}
+
+fn enemy_base(
+    name: &'static str,
+    position: Vec3,
+    patrol_route: Vec<Vec3>,
+    max_speed: f32,
+    behaviour: Handle<BehaviourTreeDefinition>,
+    tint: Color,
+    enemy_assets: &EnemyAssets,
+    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+    scale: f32,
+) -> impl Bundle {
+    let idle_layout = TextureAtlasLayout::from_grid(UVec2::splat(64), 12, 8, None, None);
+    let walk_layout = TextureAtlasLayout::from_grid(UVec2::splat(64), 8, 8, None, None);
+    let attack_layout = TextureAtlasLayout::from_grid(UVec2::new(96, 96), 7, 8, None, None);
+
+    let idle_layout = texture_atlas_layouts.add(idle_layout);
+    let walk_layout = texture_atlas_layouts.add(walk_layout);
+    let attack_layout = texture_atlas_layouts.add(attack_layout);
+
+    let character_animation = CharacterAnimation::new(AnimationCapabilities {
+        idle: CharacterAnimationData {
+            image: enemy_assets.idle.clone(),
+            atlas: TextureAtlas {
+                layout: idle_layout,
+                index: 0,
+            },
+            frames: 12,
+            interval: Duration::from_millis(150),
+        },
+        walk: Some(CharacterAnimationData {
+            image: enemy_assets.walk.clone(),
+            atlas: TextureAtlas {
+                layout: walk_layout,
+                index: 0,
+            },
+            frames: 8,
+            interval: Duration::from_millis(50),
+        }),
+        run: None,
+        attack: vec![CharacterAnimationData {
+            image: enemy_assets.attack.clone(),
+            atlas: TextureAtlas {
+                layout: attack_layout,
+                index: 0,
+            },
+            frames: 7,
+            interval: Duration::from_millis(ENEMY_ATTACK_DURATION / 7),
+        }],
+        dodge: None,
+    });
+
+    let mut sprite = character_animation.default_sprite();
+    sprite.color = tint;
+
+    let movement_controller = MovementController {
+        max_speed,
+        ..default()
+    };
+
+    let character_data = character(
+        name,
+        position,
+        sprite,
+        character_animation,
+        Collider::vertical_capsule(1.25, 0.25, position),
+        scale,
+    );
+
+    let blackboard = Blackboard::default()
+        .with(HOME_KEY, BlackboardValue::Position(position))
+        .with(PATROL_KEY, BlackboardValue::Route(patrol_route));
+
+    (
+        Enemy,
+        BehaviourTree::new(behaviour),
+        blackboard,
+        Perception::default(),
+        Awareness::default(),
+        NavPath::default(),
+        movement_controller,
+        character_data,
+        Health::new(ENEMY_MAX_HEALTH),
+        Stats::default()
+            .with_base(Stat::MaxHealth, ENEMY_MAX_HEALTH as f32)
+            .with_base(Stat::MoveSpeed, max_speed),
+        Equipment::default().with(EquipmentSlot::Armor, "leather_armor"),
+        Resistances::default(),
+    )
+}
+
+const ENEMY_MAX_HEALTH: usize = 60;
+
+const ENEMY_TINT: Color = Color::srgb(1.0, 0.55, 0.55);
+const RANGED_ENEMY_TINT: Color = Color::srgb(0.6, 0.6, 1.0);
+
+/// Must match the attack duration in the melee enemy's behaviour tree.
+const ENEMY_ATTACK_DURATION: u64 = 500;
+
+/// Blackboard key holding the position the enemy was spawned at.
+const HOME_KEY: &str = "home";
+/// Blackboard key holding the enemy's patrol route.
+const PATROL_KEY: &str = "patrol";
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Enemy;
+
+fn on_enemy_death(event: On<DeathEvent>, query: Query<(), With<Enemy>>, mut commands: Commands) {
+    if query.contains(event.event_target()) {
+        commands.trigger(DropLootEvent::new(event.event_target()));
+        commands.entity(event.event_target()).despawn();
+    }
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct EnemyAssets {
+    #[dependency]
+    idle: Handle<Image>,
+    #[dependency]
+    walk: Handle<Image>,
+    #[dependency]
+    attack: Handle<Image>,
+    #[dependency]
+    behaviour: Handle<BehaviourTreeDefinition>,
+    #[dependency]
+    ranged_behaviour: Handle<BehaviourTreeDefinition>,
+}
+
+impl FromWorld for EnemyAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            idle: assets.load("images/characters/idle.png"),
+            walk: assets.load("images/characters/walk.png"),
+            attack: assets.load("images/characters/attack.png"),
+            behaviour: assets.load("ai/melee_enemy.bt.ron"),
+            ranged_behaviour: assets.load("ai/ranged_enemy.bt.ron"),
+        }
+    }
+}
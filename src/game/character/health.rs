@@ -1,23 +1,44 @@
 use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::game::character::block::{
+    BLOCK_STAMINA_COST, BlockOutcome, STAGGER_DURATION, resolve_block,
+};
+use crate::game::character::resource_pool::{self, Pool, ResourcePool};
+use crate::game::character::stamina::{Stamina, StaminaEvent, StaminaEventType};
+use crate::game::character::{CharacterState, CharacterStateEvent, Facing};
+use crate::game::grid::coords::WorldPosition;
+use crate::screens::Screen;
+use crate::{AppSystems, PausableSystems};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_observer(on_health_event);
-}
+    app.add_plugins(resource_pool::plugin::<Health>);
 
-#[derive(Component, Asset, Clone, Reflect)]
-pub struct Health {
-    pub max: usize,
-    pub current: usize,
+    app.add_systems(
+        Update,
+        kill_fallen_characters
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    )
+    .add_observer(on_health_event);
 }
 
+/// Characters which fall below this height have left the level and die.
+const KILL_HEIGHT: f32 = -20.0;
+
+#[derive(Component, Asset, Clone, Reflect, Deref, DerefMut)]
+pub struct Health(ResourcePool);
+
+impl Pool for Health {}
+
 impl Health {
     pub fn new(max: usize) -> Self {
-        Self { max, current: max }
+        Self(ResourcePool::new(max))
     }
 
     pub fn with_current(max: usize, current: usize) -> Self {
-        let current = current.clamp(0, max);
-        Self { max, current }
+        Self(ResourcePool::new(max).with_current(current))
     }
 }
 
@@ -25,23 +46,42 @@ impl Health {
 pub struct HealthEvent {
     entity: Entity,
     event_type: HealthEventType,
+    /// The entity responsible, e.g. the attacker for damage.
+    source: Option<Entity>,
+    /// Whether the hit came from a projectile rather than from the source itself.
+    ranged: bool,
 }
 
 impl HealthEvent {
     pub fn new(entity: Entity, event_type: HealthEventType) -> Self {
-        Self { entity, event_type }
+        Self {
+            entity,
+            event_type,
+            source: None,
+            ranged: false,
+        }
+    }
+
+    pub fn with_source(mut self, source: Entity) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Marks the hit as coming from a projectile, so parrying it doesn't stagger the source.
+    pub fn ranged(mut self) -> Self {
+        self.ranged = true;
+        self
     }
 }
 
 pub enum HealthEventType {
     Heal(usize),
     Damage(usize, DamageType),
-    Set(usize),
     FullHeal,
     InstantDeath,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect, Deserialize)]
 pub enum DamageType {
     Generic,
     Fall,
@@ -56,7 +96,7 @@ pub enum DamageType {
     Explosive,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Reflect, Deserialize)]
 pub enum DamageModifier {
     None,
     Vulnerability(f32),
@@ -64,16 +104,159 @@ pub enum DamageModifier {
     Immunity,
 }
 
-fn on_health_event(event: On<HealthEvent>, mut query: Query<&mut Health>) {
-    if let Ok(mut health) = query.get_mut(event.entity) {
+impl DamageModifier {
+    fn multiplier(&self) -> f32 {
+        match self {
+            DamageModifier::None => 1.0,
+            DamageModifier::Vulnerability(amount) => 1.0 + amount,
+            DamageModifier::Resistance(amount) => (1.0 - amount).max(0.0),
+            DamageModifier::Immunity => 0.0,
+        }
+    }
+}
+
+/// How much of each type of damage a character takes, e.g. from its armor.
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct Resistances(pub Vec<(DamageType, DamageModifier)>);
+
+impl Resistances {
+    /// Applies every modifier for the damage type to the amount.
+    pub fn apply(&self, amount: usize, damage_type: DamageType) -> usize {
+        let multiplier: f32 = self
+            .0
+            .iter()
+            .filter(|(modified, _)| *modified == damage_type)
+            .map(|(_, modifier)| modifier.multiplier())
+            .product();
+
+        (amount as f32 * multiplier).round() as usize
+    }
+}
+
+/// Triggered on an entity when its health reaches zero.
+#[derive(EntityEvent, Debug, Clone, Reflect)]
+pub struct DeathEvent {
+    entity: Entity,
+}
+
+fn on_health_event(
+    event: On<HealthEvent>,
+    mut query: Query<(
+        &mut Health,
+        Option<&CharacterState>,
+        Option<&Facing>,
+        Option<&Stamina>,
+        Option<&Resistances>,
+    )>,
+    position_query: Query<&WorldPosition>,
+    mut commands: Commands,
+) {
+    if let Ok((mut health, state, facing, stamina, resistances)) = query.get_mut(event.entity) {
+        if let HealthEventType::Damage(..) = event.event_type
+            && state.is_some_and(CharacterState::is_invulnerable)
+        {
+            return;
+        }
+
+        let was_alive = !health.is_empty();
+
         match event.event_type {
-            HealthEventType::Heal(amount) => {
-                health.current += amount.min(health.max - health.current)
+            HealthEventType::Heal(amount) => health.add(amount as f32),
+            HealthEventType::Damage(amount, damage_type) => {
+                let amount = match (state, facing, event.source) {
+                    (Some(state), Some(facing), Some(source)) => mitigate_damage(
+                        event.entity,
+                        source,
+                        event.ranged,
+                        amount,
+                        state,
+                        facing,
+                        stamina.map_or(0, |stamina| stamina.current()),
+                        &position_query,
+                        &mut commands,
+                    ),
+                    _ => amount,
+                };
+                let amount = resistances
+                    .map_or(amount, |resistances| resistances.apply(amount, damage_type));
+
+                health.spend(amount as f32)
+            }
+            HealthEventType::FullHeal => health.refill(),
+            HealthEventType::InstantDeath => health.set_current(0),
+        }
+
+        if was_alive && health.is_empty() {
+            commands.trigger(DeathEvent {
+                entity: event.entity,
+            });
+        }
+    }
+}
+
+fn kill_fallen_characters(query: Query<(Entity, &WorldPosition, &Health)>, mut commands: Commands) {
+    for (entity, position, health) in &query {
+        if position.as_vec3().y < KILL_HEIGHT && !health.is_empty() {
+            commands.trigger(HealthEvent::new(entity, HealthEventType::InstantDeath));
+        }
+    }
+}
+
+/// Lets a blocking character deflect some or all of the damage of a hit. Returns the
+/// damage that still lands.
+fn mitigate_damage(
+    entity: Entity,
+    source: Entity,
+    ranged: bool,
+    amount: usize,
+    state: &CharacterState,
+    facing: &Facing,
+    stamina: usize,
+    position_query: &Query<&WorldPosition>,
+    commands: &mut Commands,
+) -> usize {
+    let (Ok(position), Ok(source_position)) =
+        (position_query.get(entity), position_query.get(source))
+    else {
+        return amount;
+    };
+
+    let direction = (source_position.as_vec3() - position.as_vec3()).xz();
+
+    match resolve_block(state, facing.direction(), direction, amount, stamina) {
+        BlockOutcome::Hit => amount,
+        BlockOutcome::Blocked(amount) => {
+            commands.trigger(StaminaEvent::new(
+                entity,
+                StaminaEventType::Spend(BLOCK_STAMINA_COST),
+            ));
+            amount
+        }
+        BlockOutcome::GuardBroken => {
+            commands.trigger(StaminaEvent::new(
+                entity,
+                StaminaEventType::Spend(BLOCK_STAMINA_COST),
+            ));
+            commands.trigger(CharacterStateEvent::new(
+                entity,
+                CharacterState::Staggered {
+                    time_left: STAGGER_DURATION,
+                },
+            ));
+            amount
+        }
+        BlockOutcome::Parried => {
+            // A projectile is deflected, but whoever fired it is out of reach.
+            if !ranged {
+                commands.trigger(CharacterStateEvent::new(
+                    source,
+                    CharacterState::Staggered {
+                        time_left: STAGGER_DURATION,
+                    },
+                ));
             }
-            HealthEventType::Damage(amount, _) => health.current -= amount.min(health.current),
-            HealthEventType::Set(amount) => health.current = amount.clamp(0, health.max),
-            HealthEventType::FullHeal => health.current = health.max,
-            HealthEventType::InstantDeath => health.current = 0,
+            0
         }
     }
 }
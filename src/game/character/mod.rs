@@ -7,18 +7,28 @@ use bevy::prelude::*;
 use std::fmt::Debug;
 
 mod animation;
+pub mod block;
+pub mod combo;
+pub mod dodge;
+pub mod enemy;
 pub mod health;
 pub mod player;
+pub mod resource_pool;
 pub mod stamina;
+pub mod stats;
 
 pub fn plugin(app: &mut App) {
     app.load_resource::<CharacterAssets>();
 
     app.add_plugins((
         animation::plugin,
+        combo::plugin,
+        dodge::plugin,
+        enemy::plugin,
         health::plugin,
         player::plugin,
         stamina::plugin,
+        stats::plugin,
     ));
     app.add_systems(Update, (update_state,).in_set(AppSystems::Update));
     app.add_observer(on_state_change);
@@ -67,7 +77,11 @@ pub enum CharacterState {
     Idle,
     Walking,
     Running,
-    Attacking { time_left: f32 },
+    Attacking { time_left: f32, step: usize },
+    Throwing { time_left: f32 },
+    Dodging { time_left: f32, velocity: Vec3 },
+    Blocking { elapsed: f32 },
+    Staggered { time_left: f32 },
 }
 
 impl CharacterState {
@@ -78,6 +92,14 @@ impl CharacterState {
             CharacterState::Idle | CharacterState::Walking | CharacterState::Running
         )
     }
+
+    /// If the character can not be damaged in this state
+    pub fn is_invulnerable(&self) -> bool {
+        matches!(
+            self,
+            CharacterState::Dodging { time_left, .. } if *time_left > dodge::DODGE_RECOVERY
+        )
+    }
 }
 
 #[derive(EntityEvent, Debug, Clone, Reflect)]
@@ -97,6 +119,10 @@ impl CharacterStateEvent {
             config: CharacterStateEventConfiguration::default(),
         }
     }
+
+    pub fn new_state(&self) -> CharacterState {
+        self.new_state
+    }
 }
 
 #[derive(Debug, Clone, Reflect)]
@@ -120,7 +146,7 @@ fn on_state_change(
         return;
     };
 
-    let prev_state = state.clone();
+    let prev_state = *state;
 
     if let Some(expected_prev_state) = event.prev_state
         && event.config.fail_on_prev_state_mismatch
@@ -138,13 +164,25 @@ fn on_state_change(
 
 fn update_state(time: Res<Time>, mut query: Query<&mut CharacterState, With<Character>>) {
     query.iter_mut().for_each(|mut state| {
-        if let CharacterState::Attacking { ref mut time_left } = *state {
+        if let CharacterState::Attacking {
+            ref mut time_left, ..
+        }
+        | CharacterState::Throwing { ref mut time_left }
+        | CharacterState::Dodging {
+            ref mut time_left, ..
+        }
+        | CharacterState::Staggered { ref mut time_left } = *state
+        {
             *time_left -= time.delta_secs();
 
             if *time_left <= 0.0 {
                 *state = CharacterState::Idle;
             }
         }
+
+        if let CharacterState::Blocking { ref mut elapsed } = *state {
+            *elapsed += time.delta_secs();
+        }
     })
 }
 
@@ -177,6 +215,16 @@ impl From<usize> for Facing {
     }
 }
 
+impl Facing {
+    /// Unit vector on the world xz plane pointing in this direction.
+    /// This is the inverse of the `From<Vec2>` conversion below.
+    pub fn direction(&self) -> Vec2 {
+        let angle =
+            *self as usize as f32 * std::f32::consts::FRAC_PI_4 - std::f32::consts::FRAC_PI_2;
+        Vec2::new(angle.sin(), angle.cos())
+    }
+}
+
 impl From<Vec2> for Facing {
     fn from(vec: Vec2) -> Self {
         // Calculate angle in radians (-PI to PI)
@@ -1,24 +1,41 @@
 //! Player-specific behavior.
 
+use bevy::input::mouse::AccumulatedMouseMotion;
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
 use std::time::Duration;
 
 use crate::game::character::animation::{
     AnimationCapabilities, CharacterAnimation, CharacterAnimationData,
 };
+use crate::game::character::combo::{Combo, ComboDefinition};
+use crate::game::character::dodge::{DODGE_DURATION, DodgeEvent};
+use crate::game::character::enemy::Enemy;
 use crate::game::character::{CharacterState, CharacterStateEvent, Facing, character};
 use crate::game::grid::coords::{
     WorldPosition, rotate_screen_space_to_facing, rotate_screen_space_to_movement,
+    screen_offset_to_ground,
 };
 //use crate::game::object::Shadow;
-use crate::game::character::health::{DamageType, Health, HealthEvent, HealthEventType};
+use crate::game::character::health::{
+    DamageType, Health, HealthEvent, HealthEventType, Resistances,
+};
+use crate::game::character::stamina::{Stamina, StaminaEvent, StaminaEventType};
+use crate::game::character::stats::{Stat, Stats};
+use crate::game::interaction::Interactor;
+use crate::game::item::ItemId;
+use crate::game::item::equipment::{Equipment, EquipmentSlot, WeaponStats};
+use crate::game::item::inventory::{Inventory, InventoryAction, InventoryEvent, ItemStack};
 use crate::game::particle::{ParticleAnimation, ParticleSpawnEvent};
 use crate::game::physics::components::{Collider, PhysicsData};
 use crate::game::physics::movement::MovementController;
-use crate::gamepad::GamepadRes;
-use crate::{AppSystems, PausableSystems, asset_tracking::LoadResource};
-use crate::game::character::stamina::{Stamina, StaminaEvent};
+use crate::game::projectile::{
+    KNIFE_FRAMES, ProjectileAssets, ProjectileDefinition, ProjectileSprite, SpawnProjectileEvent,
+    TerrainImpact,
+};
+use crate::input::{Action, ActionState, InputSource};
 use crate::screens::Screen;
+use crate::{AppSystems, PausableSystems, Scale, asset_tracking::LoadResource};
 
 pub(super) fn plugin(app: &mut App) {
     app.load_resource::<PlayerAssets>();
@@ -35,30 +52,44 @@ pub(super) fn plugin(app: &mut App) {
                 .run_if(in_state(Screen::Gameplay))
                 .in_set(AppSystems::RecordInput)
                 .in_set(PausableSystems),
-            camera_follow_player.in_set(AppSystems::Respond),
+            camera_frame_players.in_set(AppSystems::Respond),
         ),
     )
     .add_observer(on_aim_facing_changed)
     .add_observer(on_player_attack);
 }
 
-/// The player character.
+/// The player character. In local co-op, `index` tells the players apart and `source` is
+/// the device they play with.
 pub fn player(
+    index: usize,
+    source: InputSource,
     position: Vec3,
     max_speed: f32,
     player_assets: &PlayerAssets,
+    combos: &Assets<ComboDefinition>,
+    asset_server: &AssetServer,
     texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
     scale: f32,
 ) -> impl Bundle {
     let idle_layout = TextureAtlasLayout::from_grid(UVec2::splat(64), 12, 8, None, None);
     let walk_layout = TextureAtlasLayout::from_grid(UVec2::splat(64), 8, 8, None, None);
     let run_layout = TextureAtlasLayout::from_grid(UVec2::splat(64), 8, 8, None, None);
-    let attack_layout = TextureAtlasLayout::from_grid(UVec2::new(96, 96), 7, 8, None, None);
+    let dodge_layout = TextureAtlasLayout::from_grid(UVec2::splat(64), 8, 8, None, None);
 
     let idle_layout = texture_atlas_layouts.add(idle_layout);
     let walk_layout = texture_atlas_layouts.add(walk_layout);
     let run_layout = texture_atlas_layouts.add(run_layout);
-    let attack_layout = texture_atlas_layouts.add(attack_layout);
+    let dodge_layout = texture_atlas_layouts.add(dodge_layout);
+
+    let (steps, attack) = match combos.get(&player_assets.combo) {
+        Some(combo) => combo.build(asset_server, texture_atlas_layouts),
+        None => {
+            warn!("The player combo is not loaded, so the player can't attack");
+            Default::default()
+        }
+    };
+    let combo = Combo::new(steps, unarmed());
 
     let character_animation = CharacterAnimation::new(AnimationCapabilities {
         idle: CharacterAnimationData {
@@ -88,14 +119,16 @@ pub fn player(
             frames: 8,
             interval: Duration::from_millis(50),
         }),
-        attack: Some(CharacterAnimationData {
-            image: player_assets.attack.clone(),
+        attack,
+        // There is no dedicated roll animation yet, so play the run cycle once, quickly.
+        dodge: Some(CharacterAnimationData {
+            image: player_assets.run.clone(),
             atlas: TextureAtlas {
-                layout: attack_layout,
+                layout: dodge_layout,
                 index: 0,
             },
-            frames: 7,
-            interval: Duration::from_millis(ATTACK_DURATION / 7),
+            frames: 8,
+            interval: Duration::from_secs_f32(DODGE_DURATION / 8.0),
         }),
     });
 
@@ -106,6 +139,8 @@ pub fn player(
         ..default()
     };
 
+    let player = Player { index };
+
     let character_data = character(
         "Player",
         position,
@@ -138,7 +173,7 @@ pub fn player(
                 layout: indicator_ring_layout,
                 index: 0,
             }),
-            color: Color::srgba(1.0, 1.0, 1.0, 0.25),
+            color: player.color().with_alpha(0.25),
             ..default()
         },
         Visibility::Hidden,
@@ -146,11 +181,29 @@ pub fn player(
     );
 
     (
-        Player,
+        player,
+        source,
+        AimDevice::default(),
         movement_controller,
         character_data,
+        combo,
         Health::with_current(300, 400),
-        Stamina::new(200, 200, 1.0),
+        Stamina::new(200, 200.0, 1.0),
+        Stats::default()
+            .with_base(Stat::MaxHealth, 300.0)
+            .with_base(Stat::HealthRegen, 0.0)
+            .with_base(Stat::MaxStamina, 200.0)
+            .with_base(Stat::StaminaRegen, 200.0)
+            .with_base(Stat::MoveSpeed, max_speed),
+        Inventory::new(INVENTORY_SIZE)
+            .with_stack(ItemStack::new(HEALTH_POTION, 3))
+            .with_stack(ItemStack::new("war_hammer", 1))
+            .with_stack(ItemStack::new("ring_of_vigor", 1)),
+        Equipment::default()
+            .with(EquipmentSlot::Weapon, "sword")
+            .with(EquipmentSlot::Armor, "leather_armor"),
+        Resistances::default(),
+        Interactor::default(),
         Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
             parent.spawn(indicator_ring);
             //parent.spawn(shadow);
@@ -158,13 +211,71 @@ pub fn player(
     )
 }
 
+/// What the player attacks with while no weapon is equipped.
+fn unarmed() -> WeaponStats {
+    WeaponStats {
+        damage: 10,
+        damage_type: DamageType::Blunt,
+        swing_speed: 1.2,
+        stamina_cost: 15,
+    }
+}
+
+/// A knife thrown in a shallow arc, which sticks in walls and floors.
+fn throwing_knife(projectile_assets: &ProjectileAssets) -> ProjectileDefinition {
+    ProjectileDefinition {
+        speed: 9.0,
+        gravity: 4.0,
+        lifetime: 3.0,
+        damage: 15,
+        damage_type: DamageType::Pierce,
+        on_terrain: TerrainImpact::Stick,
+        sprite: ProjectileSprite {
+            image: projectile_assets.knife.clone(),
+            layout: projectile_assets.knife_layout.clone(),
+            frames: KNIFE_FRAMES,
+            interval: Duration::from_millis(100),
+            // The blade points to the top left of the image.
+            angle: 3.0 * std::f32::consts::FRAC_PI_4,
+            scale: 0.5,
+        },
+    }
+}
+
+const INVENTORY_SIZE: usize = 20;
+const HEALTH_POTION: &str = "health_potion";
+
+const THROW_DURATION: f32 = 0.3;
+const THROW_STAMINA_COST: usize = 15;
+/// Upwards component added to the throw direction, so knives fly in an arc.
+const THROW_LIFT: f32 = 0.15;
+/// How far in front of the player knives are spawned, so they clear the player's collider.
+const THROW_OFFSET: f32 = 0.5;
+
 const COYOTE_TIME: f32 = 0.2;
 const COYOTE_TIME_HEIGHT_THRESHOLD: f32 = 0.1;
 const JUMP_VELOCITY: f32 = 2.75;
 
+/// Tells the players apart in local co-op, e.g. on their indicator rings and HUD.
+const PLAYER_COLORS: [Color; 4] = [
+    Color::WHITE,
+    Color::srgb(0.5, 0.8, 1.0),
+    Color::srgb(0.6, 1.0, 0.5),
+    Color::srgb(1.0, 0.7, 0.4),
+];
+
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
 #[reflect(Component)]
-pub struct Player;
+pub struct Player {
+    /// 0 for player one, 1 for player two and so on.
+    pub index: usize,
+}
+
+impl Player {
+    pub fn color(&self) -> Color {
+        PLAYER_COLORS[self.index % PLAYER_COLORS.len()]
+    }
+}
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Default, Eq, Reflect)]
 pub struct AimFacing(pub Option<Facing>);
@@ -175,28 +286,71 @@ pub struct AimFacingEvent {
     facing: Option<Facing>,
 }
 
+/// The cursor has to be this far from the player, in unscaled pixels, to aim.
+const MOUSE_AIM_DEADZONE: f32 = 4.0;
+
+/// The device a player last aimed with. Aiming follows the mouse until the right stick
+/// is moved, and the stick until the mouse is moved or clicked. Players without a mouse
+/// only ever aim with the stick.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum AimDevice {
+    #[default]
+    Mouse,
+    Stick,
+}
+
 fn record_aim_input(
-    gamepad_res: Option<Res<GamepadRes>>,
-    gamepads: Query<&Gamepad>,
-    aim_query: Query<(Entity, &AimFacing)>,
+    scale: Res<Scale>,
+    mouse_motion: Res<AccumulatedMouseMotion>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+    mut player_query: Query<
+        (
+            &ActionState,
+            &InputSource,
+            &mut AimDevice,
+            &GlobalTransform,
+            &Children,
+        ),
+        With<Player>,
+    >,
+    aim_query: Query<&AimFacing>,
     mut commands: Commands,
 ) {
-    // Add gamepad input if available
-    if let Some(gamepad_res) = gamepad_res
-        && let Ok(gamepad) = gamepads.get(gamepad_res.0)
-    {
-        let right_stick_x = gamepad.get(GamepadAxis::RightStickX).unwrap_or(0.0);
-        let right_stick_y = gamepad.get(GamepadAxis::RightStickY).unwrap_or(0.0);
+    let mouse_used = mouse_motion.delta != Vec2::ZERO || mouse_buttons.get_just_pressed().len() > 0;
+
+    for (action_state, source, mut aim_device, player_transform, children) in &mut player_query {
+        let right_stick = action_state.axis_pair(
+            Action::AimUp,
+            Action::AimDown,
+            Action::AimLeft,
+            Action::AimRight,
+        );
+        let stick_active = right_stick != Vec2::ZERO;
+        let has_mouse = matches!(source, InputSource::All | InputSource::KeyboardMouse);
+
+        if stick_active {
+            *aim_device = AimDevice::Stick;
+        } else if has_mouse && mouse_used {
+            *aim_device = AimDevice::Mouse;
+        }
 
-        // Apply deadzone
-        let new_facing = if right_stick_x.abs() > 0.1 || right_stick_y.abs() > 0.1 {
-            let aim_direction = Vec2::new(right_stick_x, right_stick_y);
-            Some(Facing::from(rotate_screen_space_to_facing(aim_direction)))
-        } else {
-            None
+        let new_facing = match *aim_device {
+            AimDevice::Mouse if has_mouse => {
+                mouse_aim(&window_query, &camera_query, player_transform, scale.0)
+            }
+            _ => stick_active.then(|| Facing::from(rotate_screen_space_to_facing(right_stick))),
         };
 
-        if let Ok((aiming_entity, aim_facing)) = aim_query.single()
+        let aiming = children.iter().find_map(|child| {
+            aim_query
+                .get(child)
+                .ok()
+                .map(|aim_facing| (child, aim_facing))
+        });
+
+        if let Some((aiming_entity, aim_facing)) = aiming
             && new_facing != aim_facing.0
         {
             commands.trigger(AimFacingEvent {
@@ -207,6 +361,24 @@ fn record_aim_input(
     }
 }
 
+/// Aims from the player towards the cursor, projected onto the ground at the player's
+/// height.
+fn mouse_aim(
+    window_query: &Query<&Window, With<PrimaryWindow>>,
+    camera_query: &Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+    player_transform: &GlobalTransform,
+    scale: f32,
+) -> Option<Facing> {
+    let cursor = window_query.single().ok()?.cursor_position()?;
+    let (camera, camera_transform) = camera_query.single().ok()?;
+
+    let cursor = camera.viewport_to_world_2d(camera_transform, cursor).ok()?;
+    // The player's transform is scaled up, the projection works in unscaled pixels.
+    let offset = (cursor - player_transform.translation().xy()) / scale;
+
+    (offset.length() > MOUSE_AIM_DEADZONE).then(|| Facing::from(screen_offset_to_ground(offset)))
+}
+
 fn on_aim_facing_changed(
     event: On<AimFacingEvent>,
     mut query: Query<(&mut AimFacing, &mut Sprite, &mut Visibility)>,
@@ -230,81 +402,36 @@ fn on_aim_facing_changed(
 }
 
 fn record_player_movement_input(
-    input: Res<ButtonInput<KeyCode>>,
-    gamepad_res: Option<Res<GamepadRes>>,
-    gamepads: Query<&Gamepad>,
     mut controller_query: Query<
         (
             Entity,
+            &ActionState,
             &mut MovementController,
             &PhysicsData,
             &WorldPosition,
             &CharacterState,
+            Option<&Stamina>,
         ),
         With<Player>,
     >,
     mut commands: Commands,
 ) {
-    let mut intent = Vec3::ZERO;
-
-    let mut is_jumping = false;
-
-    let mut toggle_run = false;
-
-    // Add gamepad input if available
-    if let Some(gamepad_res) = gamepad_res
-        && let Ok(gamepad) = gamepads.get(gamepad_res.0)
+    // Apply each player's movement intent to their controller.
+    for (entity, action_state, mut controller, physics, position, state, stamina) in
+        &mut controller_query
     {
-        let left_stick_x = gamepad.get(GamepadAxis::LeftStickX).unwrap_or(0.0);
-        let left_stick_y = gamepad.get(GamepadAxis::LeftStickY).unwrap_or(0.0);
+        let movement = action_state.axis_pair(
+            Action::MoveUp,
+            Action::MoveDown,
+            Action::MoveLeft,
+            Action::MoveRight,
+        );
+        let intent = rotate_screen_space_to_movement(Vec3::new(movement.x, 0.0, -movement.y));
 
-        // Apply deadzone
-        if left_stick_x.abs() > 0.1 || left_stick_y.abs() > 0.1 {
-            intent.x += left_stick_x;
-            intent.z -= left_stick_y;
+        let is_jumping = action_state.just_pressed(Action::Jump);
 
-            intent = rotate_screen_space_to_movement(intent);
-        }
+        let toggle_run = action_state.just_pressed(Action::ToggleRun);
 
-        if gamepad.just_pressed(GamepadButton::South) {
-            is_jumping = true;
-        }
-
-        if gamepad.just_pressed(GamepadButton::LeftThumb) {
-            toggle_run = true;
-        }
-    }
-
-    if intent == Vec3::ZERO {
-        // Collect directional input.
-        if input.pressed(KeyCode::KeyW) || input.pressed(KeyCode::ArrowUp) {
-            intent.z -= 1.0;
-        }
-        if input.pressed(KeyCode::KeyS) || input.pressed(KeyCode::ArrowDown) {
-            intent.z += 1.0;
-        }
-        if input.pressed(KeyCode::KeyA) || input.pressed(KeyCode::ArrowLeft) {
-            intent.x -= 1.0;
-        }
-        if input.pressed(KeyCode::KeyD) || input.pressed(KeyCode::ArrowRight) {
-            intent.x += 1.0;
-        }
-
-        if input.just_pressed(KeyCode::Space) {
-            is_jumping = true;
-        }
-
-        if input.just_pressed(KeyCode::ShiftLeft) {
-            toggle_run = true;
-        }
-
-        // Normalize intent so that diagonal movement is the same speed as horizontal / vertical.
-        intent = intent.normalize_or_zero();
-        intent = rotate_screen_space_to_movement(intent);
-    }
-
-    // Apply movement intent to controllers.
-    for (entity, mut controller, physics, position, state) in &mut controller_query {
         if !state.is_movement() {
             controller.intent = Vec3::ZERO;
             continue;
@@ -312,8 +439,10 @@ fn record_player_movement_input(
 
         controller.intent = intent;
 
+        let exhausted = stamina.is_some_and(|stamina| stamina.exhausted);
+
         let new_state = if intent.length() > 1e-6 {
-            if intent.length() < 0.7 {
+            if intent.length() < 0.7 || exhausted {
                 controller.running = false;
                 CharacterState::Walking
             } else {
@@ -352,106 +481,268 @@ fn record_player_movement_input(
 }
 
 fn record_action_input(
-    _input: Res<ButtonInput<KeyCode>>,
-    gamepad_res: Option<Res<GamepadRes>>,
-    gamepads: Query<&Gamepad>,
-    mut player_query: Query<(Entity, &CharacterState, &mut Facing, &Stamina), With<Player>>,
+    projectile_assets: Res<ProjectileAssets>,
+    mut player_query: Query<
+        (
+            Entity,
+            &ActionState,
+            &CharacterState,
+            &mut Facing,
+            &Stamina,
+            &mut Combo,
+            &WorldPosition,
+            &Stats,
+            &Inventory,
+            &Children,
+        ),
+        With<Player>,
+    >,
     aim_facing_query: Query<&AimFacing>,
     mut commands: Commands,
 ) {
-    if let Some(gamepad_res) = gamepad_res
-        && let Ok(gamepad) = gamepads.get(gamepad_res.0)
+    for (
+        player,
+        action_state,
+        state,
+        mut facing,
+        stamina,
+        mut combo,
+        position,
+        stats,
+        inventory,
+        children,
+    ) in &mut player_query
     {
-        let Ok((player, state, mut facing, stamina)) = player_query.single_mut() else {
-            return;
-        };
+        // Start a buffered swing as soon as the current one can be cancelled into it.
+        let queued = combo.take_queued(state);
+        if let Some(step) = queued
+            && stamina.current() > 0
+        {
+            commands.trigger(PlayerAttackEvent {
+                entity: player,
+                facing: *facing,
+                step,
+            });
+        }
 
-        let Ok(aim_facing) = aim_facing_query.single() else {
-            panic!("Singular aim facing not found");
-        };
+        let aim_facing = children
+            .iter()
+            .find_map(|child| aim_facing_query.get(child).ok())
+            .and_then(|aim_facing| aim_facing.0);
 
-        if gamepad.just_pressed(GamepadButton::West) {
+        if action_state.just_pressed(Action::DebugDamage) {
             commands.trigger(HealthEvent::new(
                 player,
                 HealthEventType::Damage(10, DamageType::Generic),
             ));
         }
 
-        if gamepad.just_pressed(GamepadButton::North) {
-            commands.trigger(HealthEvent::new(player, HealthEventType::Heal(10)));
+        if action_state.just_pressed(Action::DebugHeal) {
+            commands.trigger(HealthEvent::new(player, HealthEventType::FullHeal));
         }
 
-        if gamepad.just_pressed(GamepadButton::RightTrigger) && state.is_movement() && stamina.current > 0 {
-            if let Some(aim_facing) = aim_facing.0 {
+        // Quick-use a health potion.
+        if action_state.just_pressed(Action::QuickHeal)
+            && let Some(slot) = inventory.find(&ItemId::from(HEALTH_POTION))
+        {
+            commands.trigger(InventoryEvent::new(player, InventoryAction::Use(slot)));
+        }
+
+        if action_state.just_pressed(Action::Dodge) {
+            let movement = action_state.axis_pair(
+                Action::MoveUp,
+                Action::MoveDown,
+                Action::MoveLeft,
+                Action::MoveRight,
+            );
+
+            // Dodge along the movement direction, or the facing direction when standing still.
+            let direction =
+                rotate_screen_space_to_movement(Vec3::new(movement.x, 0.0, -movement.y)).xz();
+
+            commands.trigger(DodgeEvent::new(player, direction));
+        }
+
+        // Hold to block.
+        if action_state.pressed(Action::Block) {
+            if state.is_movement() {
+                if let Some(aim_facing) = aim_facing {
+                    *facing = aim_facing;
+                }
+
+                commands.trigger(CharacterStateEvent::new(
+                    player,
+                    CharacterState::Blocking { elapsed: 0.0 },
+                ));
+            }
+        } else if let CharacterState::Blocking { .. } = state {
+            commands.trigger(CharacterStateEvent::new(player, CharacterState::Idle));
+        }
+
+        // The state only changes once the queued step's event is handled, so an attack
+        // pressed this frame would otherwise queue the step after it as well.
+        if queued.is_none()
+            && action_state.just_pressed(Action::Attack)
+            && let Some(step) = combo.request_attack(state)
+            && stamina.current() > 0
+        {
+            if let Some(aim_facing) = aim_facing {
                 *facing = aim_facing;
             }
 
             commands.trigger(PlayerAttackEvent {
                 entity: player,
                 facing: *facing,
+                step,
             });
         }
+
+        if action_state.just_pressed(Action::Throw)
+            && state.is_movement()
+            && stamina.current() >= THROW_STAMINA_COST
+        {
+            if let Some(aim_facing) = aim_facing {
+                *facing = aim_facing;
+            }
+
+            let direction = facing.direction();
+            let direction = Vec3::new(direction.x, 0.0, direction.y);
+
+            let mut knife = throwing_knife(&projectile_assets);
+            knife.damage = stats.attack_damage(knife.damage);
+
+            commands.trigger(StaminaEvent::new(
+                player,
+                StaminaEventType::Spend(THROW_STAMINA_COST),
+            ));
+            commands.trigger(SpawnProjectileEvent {
+                owner: player,
+                position: position.as_vec3() + direction * THROW_OFFSET,
+                direction: direction + Vec3::Y * THROW_LIFT,
+                definition: knife,
+            });
+            commands.trigger(CharacterStateEvent::new(
+                player,
+                CharacterState::Throwing {
+                    time_left: THROW_DURATION,
+                },
+            ));
+        }
     }
 }
 
-fn camera_follow_player(
-    player_query: Query<&mut Transform, (With<Player>, Without<Camera2d>)>,
-    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+/// How much of the window to leave free around the players before zooming out.
+const CAMERA_FRAMING_MARGIN: f32 = 0.25;
+/// Players further apart than this zoom allows can end up off screen.
+const CAMERA_MAX_ZOOM_OUT: f32 = 2.0;
+
+/// Centers the camera between the players, zooming out when they don't all fit on screen.
+fn camera_frame_players(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    player_query: Query<&Transform, (With<Player>, Without<Camera2d>)>,
+    mut camera_query: Query<(&mut Transform, &mut Projection), With<Camera2d>>,
 ) {
-    let Ok(player_transform) = player_query.single() else {
+    let Ok((mut camera_transform, mut projection)) = camera_query.single_mut() else {
         return;
     };
 
-    let Ok(mut camera_transform) = camera_query.single_mut() else {
-        return;
+    let bounds = player_query
+        .iter()
+        .map(|transform| (transform.translation, transform.translation))
+        .reduce(|(min, max), (translation, _)| (min.min(translation), max.max(translation)));
+
+    let zoom = match (bounds, window_query.single()) {
+        (Some((min, max)), Ok(window)) => {
+            // Update camera position to the middle of the players
+            camera_transform.translation = (min + max) / 2.0;
+
+            let room = window.size() * (1.0 - CAMERA_FRAMING_MARGIN);
+            ((max - min).xy() / room)
+                .max_element()
+                .clamp(1.0, CAMERA_MAX_ZOOM_OUT)
+        }
+        _ => 1.0,
     };
 
-    // Update camera position to match player position
-    camera_transform.translation = player_transform.translation;
+    if let Projection::Orthographic(orthographic) = &mut *projection {
+        orthographic.scale = zoom;
+    }
 }
 
 #[derive(EntityEvent, Debug, Clone, Reflect)]
 struct PlayerAttackEvent {
     entity: Entity,
     facing: Facing,
+    /// Index of the swing in the player's [`Combo`].
+    step: usize,
 }
 
-const ATTACK_DURATION: u64 = 350;
-const ATTACK_STAMINA_COST: usize = 20;
-
 fn on_player_attack(
     event: On<PlayerAttackEvent>,
-    player_assets: Res<PlayerAssets>,
-    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    player_query: Query<(&WorldPosition, &Combo, &Stats), With<Player>>,
+    enemy_query: Query<(Entity, &WorldPosition), With<Enemy>>,
     mut commands: Commands,
 ) {
-    commands.trigger(StaminaEvent::new(event.entity, ATTACK_STAMINA_COST));
+    let Ok((player_position, combo, stats)) = player_query.get(event.entity) else {
+        return;
+    };
+
+    let Some(step) = combo.steps.get(event.step) else {
+        return;
+    };
+
+    let weapon = &combo.weapon;
+    let duration = step.duration(weapon);
+
+    commands.trigger(StaminaEvent::new(
+        event.entity,
+        StaminaEventType::Spend(step.stamina_cost(weapon)),
+    ));
+
+    let damage = stats.attack_damage(step.damage(weapon));
+    let player_position = player_position.as_vec3();
+    let attack_direction = event.facing.direction();
+
+    for (enemy, enemy_position) in &enemy_query {
+        let offset = (enemy_position.as_vec3() - player_position).xz();
+
+        if offset.length() <= step.range
+            && offset.normalize_or_zero().dot(attack_direction) >= step.arc_cos
+        {
+            commands.trigger(
+                HealthEvent::new(enemy, HealthEventType::Damage(damage, weapon.damage_type))
+                    .with_source(event.entity),
+            );
+        }
+    }
 
     commands.trigger(CharacterStateEvent::new(
         event.entity,
         CharacterState::Attacking {
-            time_left: ATTACK_DURATION as f32 / 1000.0,
+            time_left: duration,
+            step: event.step,
         },
     ));
 
-    const NUM_FRAMES: u32 = 7;
-
-    let particle_layout =
-        TextureAtlasLayout::from_grid(UVec2::new(96, 96), NUM_FRAMES, 8, None, None);
-    let particle_layout = texture_atlas_layouts.add(particle_layout);
+    let Some(particle) = &step.particle else {
+        return;
+    };
 
-    let particle_sprite = Sprite::from_atlas_image(
-        player_assets.attack_particle.clone(),
-        TextureAtlas {
-            layout: particle_layout,
-            index: event.facing as usize,
-        },
-    );
+    let particle_sprite = Sprite {
+        custom_size: Some(particle.size),
+        ..Sprite::from_atlas_image(
+            particle.image.clone(),
+            TextureAtlas {
+                layout: particle.layout.clone(),
+                index: event.facing as usize * particle.frames,
+            },
+        )
+    };
 
     let particle_animation = ParticleAnimation::new(
-        event.facing as usize * NUM_FRAMES as usize,
-        NUM_FRAMES as usize,
-        Duration::from_millis(ATTACK_DURATION / NUM_FRAMES as u64),
+        event.facing as usize * particle.frames,
+        particle.frames,
+        Duration::from_secs_f32(duration / particle.frames as f32),
     );
 
     commands.trigger(ParticleSpawnEvent::with_parent(
@@ -471,10 +762,9 @@ pub struct PlayerAssets {
     #[dependency]
     run: Handle<Image>,
 
+    /// The swings of the player's attack combo.
     #[dependency]
-    attack: Handle<Image>,
-    #[dependency]
-    attack_particle: Handle<Image>,
+    combo: Handle<ComboDefinition>,
 
     #[dependency]
     indicator_ring: Handle<Image>,
@@ -491,8 +781,7 @@ impl FromWorld for PlayerAssets {
             walk: assets.load("images/characters/walk.png"),
             run: assets.load("images/characters/run.png"),
 
-            attack: assets.load("images/characters/attack.png"),
-            attack_particle: assets.load("images/characters/attack_particle.png"),
+            combo: assets.load("combos/player.combo.ron"),
 
             indicator_ring: assets.load("images/characters/indicator_ring.png"),
 
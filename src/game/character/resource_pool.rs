@@ -0,0 +1,305 @@
+//! Bookkeeping shared by character stats which fill up to a maximum and regenerate over
+//! time, such as [`Health`](crate::game::character::health::Health) and
+//! [`Stamina`](crate::game::character::stamina::Stamina).
+
+use bevy::ecs::component::Mutable;
+use bevy::prelude::*;
+use std::marker::PhantomData;
+use std::ops::DerefMut;
+
+use crate::screens::Screen;
+use crate::{AppSystems, PausableSystems};
+
+/// Regenerates every `T` and reports changes to it with a [`PoolChangedEvent`].
+pub(super) fn plugin<T: Pool>(app: &mut App) {
+    app.add_systems(
+        Update,
+        (regenerate_pools::<T>, report_pool_changes::<T>)
+            .chain()
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// A component built on a [`ResourcePool`].
+pub trait Pool: Component<Mutability = Mutable> + DerefMut<Target = ResourcePool> {}
+
+/// An amount which can be spent and regenerated, up to a maximum. The amount is tracked
+/// fractionally, so slow regeneration and continuous drains add up correctly.
+#[derive(Debug, Clone, Reflect)]
+pub struct ResourcePool {
+    pub max: usize,
+    value: f32,
+    /// Amount regenerated per second, before applying the curve and scale.
+    pub regen_per_second: f32,
+    /// Seconds after spending before regeneration starts.
+    pub regen_delay: f32,
+    pub regen_curve: RegenCurve,
+    /// Multiplier on the regeneration rate, e.g. to slow it down temporarily.
+    pub regen_scale: f32,
+    /// Seconds left before regeneration starts.
+    delay_left: f32,
+    /// Seconds spent regenerating since the delay ended.
+    regen_time: f32,
+    /// The value last reported with a [`PoolChangedEvent`].
+    reported: usize,
+}
+
+/// How the regeneration rate of a [`ResourcePool`] develops.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Reflect)]
+pub enum RegenCurve {
+    /// The full rate as soon as the delay has passed.
+    #[default]
+    Constant,
+    /// Ramps up from nothing to the full rate over the given number of seconds.
+    RampUp(f32),
+    /// Faster the emptier the pool is, from 1.5 times the rate when empty down to half of
+    /// it when nearly full.
+    Deficit,
+}
+
+impl ResourcePool {
+    /// A full pool which does not regenerate.
+    pub fn new(max: usize) -> Self {
+        Self {
+            max,
+            value: max as f32,
+            regen_per_second: 0.0,
+            regen_delay: 0.0,
+            regen_curve: RegenCurve::Constant,
+            regen_scale: 1.0,
+            delay_left: 0.0,
+            regen_time: 0.0,
+            reported: max,
+        }
+    }
+
+    pub fn with_current(mut self, current: usize) -> Self {
+        self.set_current(current);
+        self.reported = self.current();
+        self
+    }
+
+    pub fn with_regen(mut self, per_second: f32, delay: f32) -> Self {
+        self.regen_per_second = per_second;
+        self.regen_delay = delay;
+        self
+    }
+
+    pub fn with_curve(mut self, curve: RegenCurve) -> Self {
+        self.regen_curve = curve;
+        self
+    }
+
+    /// The whole amount currently in the pool.
+    pub fn current(&self) -> usize {
+        self.value as usize
+    }
+
+    /// How full the pool is, between 0 and 1.
+    pub fn fraction(&self) -> f32 {
+        if self.max == 0 {
+            return 0.0;
+        }
+        self.value / self.max as f32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.current() == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.value >= self.max as f32
+    }
+
+    /// Adds to the pool without affecting regeneration.
+    pub fn add(&mut self, amount: f32) {
+        self.value = (self.value + amount).clamp(0.0, self.max as f32);
+    }
+
+    /// Takes from the pool and restarts the regeneration delay.
+    pub fn spend(&mut self, amount: f32) {
+        self.add(-amount);
+        self.delay_regen(self.regen_delay);
+    }
+
+    pub fn set_current(&mut self, value: usize) {
+        self.value = value.min(self.max) as f32;
+    }
+
+    pub fn refill(&mut self) {
+        self.value = self.max as f32;
+    }
+
+    /// Changes the maximum, keeping the current amount unless it no longer fits.
+    pub fn set_max(&mut self, max: usize) {
+        self.max = max;
+        self.value = self.value.min(max as f32);
+    }
+
+    /// Keeps the pool from regenerating for at least `seconds`.
+    pub fn delay_regen(&mut self, seconds: f32) {
+        self.delay_left = self.delay_left.max(seconds);
+        self.regen_time = 0.0;
+    }
+
+    /// Whether [`regenerate`](Self::regenerate) has anything to do, so full pools aren't
+    /// flagged as changed every frame.
+    fn is_regenerating(&self) -> bool {
+        self.delay_left > 0.0 || (!self.is_full() && self.regen_per_second > 0.0)
+    }
+
+    fn regenerate(&mut self, delta: f32) {
+        if self.delay_left > 0.0 {
+            self.delay_left = (self.delay_left - delta).max(0.0);
+            return;
+        }
+
+        if self.is_full() || self.regen_per_second <= 0.0 {
+            return;
+        }
+
+        self.regen_time += delta;
+
+        let curve = match self.regen_curve {
+            RegenCurve::Constant => 1.0,
+            RegenCurve::RampUp(seconds) if seconds > 0.0 => (self.regen_time / seconds).min(1.0),
+            RegenCurve::RampUp(_) => 1.0,
+            RegenCurve::Deficit => 1.5 - self.fraction(),
+        };
+
+        self.add(self.regen_per_second * self.regen_scale * curve * delta);
+    }
+}
+
+/// Triggered on an entity when the whole amount in its `T` pool changes.
+#[derive(EntityEvent)]
+pub struct PoolChangedEvent<T: Pool> {
+    entity: Entity,
+    pub previous: usize,
+    pub current: usize,
+    marker: PhantomData<T>,
+}
+
+fn regenerate_pools<T: Pool>(time: Res<Time>, mut query: Query<&mut T>) {
+    for mut pool in &mut query {
+        if pool.is_regenerating() {
+            pool.regenerate(time.delta_secs());
+        }
+    }
+}
+
+fn report_pool_changes<T: Pool>(
+    mut query: Query<(Entity, &mut T), Changed<T>>,
+    mut commands: Commands,
+) {
+    for (entity, mut pool) in &mut query {
+        let current = pool.current();
+
+        if current != pool.reported {
+            commands.trigger(PoolChangedEvent::<T> {
+                entity,
+                previous: pool.reported,
+                current,
+                marker: PhantomData,
+            });
+            pool.reported = current;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty(max: usize, per_second: f32, delay: f32) -> ResourcePool {
+        ResourcePool::new(max)
+            .with_current(0)
+            .with_regen(per_second, delay)
+    }
+
+    #[test]
+    fn fractional_regen_adds_up() {
+        let mut pool = empty(10, 1.0, 0.0);
+
+        for _ in 0..3 {
+            pool.regenerate(0.25);
+        }
+        assert_eq!(pool.current(), 0);
+
+        pool.regenerate(0.25);
+        assert_eq!(pool.current(), 1);
+    }
+
+    #[test]
+    fn spending_delays_regen() {
+        let mut pool = ResourcePool::new(10).with_regen(2.0, 1.0);
+        pool.spend(5.0);
+
+        // A shorter delay doesn't cut the running one short.
+        pool.delay_regen(0.25);
+
+        pool.regenerate(0.5);
+        pool.regenerate(0.5);
+        assert_eq!(pool.current(), 5);
+
+        pool.regenerate(0.5);
+        assert_eq!(pool.current(), 6);
+    }
+
+    #[test]
+    fn full_pools_stop_regenerating() {
+        let mut pool = ResourcePool::new(10).with_regen(2.0, 1.0);
+        assert!(!pool.is_regenerating());
+
+        pool.spend(1.0);
+        assert!(pool.is_regenerating());
+
+        pool.regenerate(1.0);
+        pool.regenerate(1.0);
+        assert!(pool.is_full());
+        assert!(!pool.is_regenerating());
+    }
+
+    #[test]
+    fn ramp_up_starts_slow() {
+        let mut pool = empty(10, 4.0, 0.0).with_curve(RegenCurve::RampUp(1.0));
+
+        pool.regenerate(0.5);
+        assert_eq!(pool.value, 1.0);
+
+        pool.regenerate(0.5);
+        assert_eq!(pool.value, 3.0);
+
+        pool.regenerate(0.5);
+        assert_eq!(pool.value, 5.0);
+    }
+
+    #[test]
+    fn deficit_regens_faster_when_emptier() {
+        let mut low = empty(10, 1.0, 0.0).with_curve(RegenCurve::Deficit);
+        let mut high = low.clone().with_current(8);
+
+        low.regenerate(1.0);
+        high.regenerate(1.0);
+
+        assert!((low.value - 1.5).abs() < 1e-5);
+        assert!((high.value - 8.7).abs() < 1e-5);
+    }
+
+    #[test]
+    fn shrinking_max_clamps_the_value() {
+        let mut pool = ResourcePool::new(10);
+
+        pool.set_max(6);
+        assert_eq!(pool.current(), 6);
+        assert!(pool.is_full());
+
+        pool.set_max(12);
+        assert_eq!(pool.current(), 6);
+        assert!(!pool.is_full());
+
+        assert_eq!(ResourcePool::new(5).with_current(9).current(), 5);
+    }
+}
@@ -1,42 +1,66 @@
+use crate::game::character::CharacterState;
+use crate::game::character::resource_pool::{
+    self, Pool, PoolChangedEvent, RegenCurve, ResourcePool,
+};
+use crate::game::physics::movement::MovementController;
 use crate::screens::Screen;
 use crate::{AppSystems, PausableSystems};
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(resource_pool::plugin::<Stamina>);
+
     app.add_systems(
         Update,
-        (
-            update_stamina_timer.in_set(AppSystems::TickTimers),
-            update_stamina.in_set(AppSystems::Update),
-        )
+        drain_sprint_stamina
             .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update)
             .in_set(PausableSystems),
     )
-    .add_observer(on_stamina_event);
+    .add_observer(on_stamina_event)
+    .add_observer(on_stamina_changed);
 }
 
-#[derive(Component)]
+/// Stamina spent per second while running.
+const SPRINT_STAMINA_PER_SECOND: f32 = 30.0;
+/// Fraction of the normal regeneration rate while exhausted.
+const EXHAUSTED_REGEN_FRACTION: f32 = 0.5;
+/// Fraction of maximum stamina which needs to be regained to stop being exhausted.
+const EXHAUSTION_RECOVERY_FRACTION: f32 = 0.3;
+/// Seconds for regeneration to reach its full rate once the delay has passed.
+const REGEN_RAMP_UP: f32 = 0.5;
+
+#[derive(Component, Deref, DerefMut)]
 pub struct Stamina {
-    pub max: usize,
-    pub current: usize,
-    pub regen_rate: usize,
-    pub regen_delay: f32,
-    pub regen_timer: Timer,
-    pub regen_delay_timer: Option<Timer>,
+    #[deref]
+    pool: ResourcePool,
+    /// Set when stamina runs out. An exhausted character can only walk and regenerates
+    /// more slowly until it has recovered some stamina.
+    pub exhausted: bool,
 }
 
-impl Stamina {
-    pub fn new(max: usize, regen_per_second: usize, regen_delay: f32) -> Self {
-        let regen_interval = 0.1;
-        let regen_rate = (regen_per_second as f32 * regen_interval).max(0.0) as usize;
+impl Pool for Stamina {}
 
+impl Stamina {
+    pub fn new(max: usize, regen_per_second: f32, regen_delay: f32) -> Self {
         Self {
-            max,
-            current: max,
-            regen_rate,
-            regen_delay,
-            regen_timer: Timer::from_seconds(regen_interval, TimerMode::Repeating),
-            regen_delay_timer: None,
+            pool: ResourcePool::new(max)
+                .with_regen(regen_per_second, regen_delay)
+                .with_curve(RegenCurve::RampUp(REGEN_RAMP_UP)),
+            exhausted: false,
+        }
+    }
+
+    /// Spends `cost` stamina and delays regeneration, becoming exhausted if it runs out.
+    fn spend(&mut self, cost: f32) {
+        self.pool.spend(cost);
+
+        if self.pool.is_empty() {
+            self.exhausted = true;
+            self.pool.regen_scale = EXHAUSTED_REGEN_FRACTION;
+
+            let regen_delay = self.pool.regen_delay * 2.0;
+            self.pool.delay_regen(regen_delay);
         }
     }
 }
@@ -44,51 +68,66 @@ impl Stamina {
 #[derive(EntityEvent)]
 pub struct StaminaEvent {
     entity: Entity,
-    cost: usize,
+    event_type: StaminaEventType,
 }
 
 impl StaminaEvent {
-    pub fn new(entity: Entity, cost: usize) -> Self {
-        Self { entity, cost }
+    pub fn new(entity: Entity, event_type: StaminaEventType) -> Self {
+        Self { entity, event_type }
     }
 }
 
+pub enum StaminaEventType {
+    Spend(usize),
+    /// Adds stamina without affecting regeneration, e.g. from a potion.
+    Restore(usize),
+}
+
 fn on_stamina_event(event: On<StaminaEvent>, mut query: Query<&mut Stamina>) {
     if let Ok(mut stamina) = query.get_mut(event.entity) {
-        stamina.current = (stamina.current as isize - event.cost as isize).max(0) as usize;
-
-        let regen_delay = if stamina.current > 0 {
-            stamina.regen_delay
-        } else {
-            stamina.regen_delay * 2.0
-        };
-
-        stamina.regen_delay_timer = Some(Timer::from_seconds(regen_delay, TimerMode::Once));
+        match event.event_type {
+            StaminaEventType::Spend(cost) => stamina.spend(cost as f32),
+            StaminaEventType::Restore(amount) => stamina.add(amount as f32),
+        }
     }
 }
 
-fn update_stamina_timer(time: Res<Time>, mut query: Query<&mut Stamina>) {
-    for mut stamina in &mut query {
-        stamina.regen_timer.tick(time.delta());
-        if let Some(ref mut timer) = stamina.regen_delay_timer {
-            timer.tick(time.delta());
+fn on_stamina_changed(event: On<PoolChangedEvent<Stamina>>, mut query: Query<&mut Stamina>) {
+    let Ok(mut stamina) = query.get_mut(event.event_target()) else {
+        return;
+    };
 
-            if timer.is_finished() {
-                stamina.regen_delay_timer = None;
-            }
-        }
+    let recovery_threshold = stamina.max as f32 * EXHAUSTION_RECOVERY_FRACTION;
+
+    if stamina.exhausted
+        && event.current > event.previous
+        && event.current as f32 >= recovery_threshold
+    {
+        stamina.exhausted = false;
+        stamina.regen_scale = 1.0;
     }
 }
 
-fn update_stamina(mut query: Query<&mut Stamina>) {
-    for mut stamina in &mut query {
-        if stamina.regen_delay_timer.is_some() {
+/// Drains stamina from running characters, which also keeps it from regenerating.
+fn drain_sprint_stamina(
+    time: Res<Time>,
+    mut query: Query<(
+        &mut Stamina,
+        &CharacterState,
+        Option<&mut MovementController>,
+    )>,
+) {
+    for (mut stamina, state, controller) in &mut query {
+        if !matches!(state, CharacterState::Running) {
             continue;
         }
 
-        if stamina.regen_timer.is_finished() {
-            stamina.current += stamina.regen_rate;
-            stamina.current = stamina.current.min(stamina.max);
+        stamina.spend(SPRINT_STAMINA_PER_SECOND * time.delta_secs());
+
+        if stamina.exhausted
+            && let Some(mut controller) = controller
+        {
+            controller.running = false;
         }
     }
 }
@@ -0,0 +1,294 @@
+//! Character attributes built from base values and stacking modifiers, e.g. from potions,
+//! equipment or status effects.
+//!
+//! The derived values are written to the components they affect whenever [`Stats`]
+//! change, so those components should not be changed directly on characters with stats.
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::game::character::health::Health;
+use crate::game::character::stamina::Stamina;
+use crate::game::physics::movement::MovementController;
+use crate::screens::Screen;
+use crate::{AppSystems, PausableSystems};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            update_modifier_timers.in_set(AppSystems::TickTimers),
+            apply_stats.in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    )
+    .add_observer(on_add_modifiers);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Deserialize)]
+pub enum Stat {
+    /// Flows into [`Health`]'s maximum.
+    MaxHealth,
+    /// Flows into [`Stamina`]'s maximum.
+    MaxStamina,
+    /// Flows into [`MovementController::max_speed`].
+    MoveSpeed,
+    /// Multiplier on the damage of the character's attacks, 1 unless set.
+    AttackDamage,
+    /// Health regenerated per second.
+    HealthRegen,
+    /// Stamina regenerated per second.
+    StaminaRegen,
+}
+
+/// A change to one stat. All additive modifiers of a stat are summed up before the
+/// multiplicative ones are applied.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, Deserialize)]
+pub enum StatModifier {
+    Add(Stat, f32),
+    Multiply(Stat, f32),
+}
+
+impl StatModifier {
+    pub fn stat(&self) -> Stat {
+        match self {
+            StatModifier::Add(stat, _) | StatModifier::Multiply(stat, _) => *stat,
+        }
+    }
+}
+
+/// A modifier together with where it came from and how long it lasts.
+#[derive(Debug, Clone, Reflect)]
+struct ActiveModifier {
+    source: String,
+    modifier: StatModifier,
+    /// Runs until the modifier expires. Modifiers without one last until removed.
+    timer: Option<Timer>,
+}
+
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct Stats {
+    base: HashMap<Stat, f32>,
+    modifiers: Vec<ActiveModifier>,
+}
+
+impl Stats {
+    pub fn with_base(mut self, stat: Stat, value: f32) -> Self {
+        self.base.insert(stat, value);
+        self
+    }
+
+    /// The value of the stat after applying all modifiers, if it has a base value.
+    pub fn get(&self, stat: Stat) -> Option<f32> {
+        self.base.get(&stat).map(|base| self.modify(stat, *base))
+    }
+
+    /// The value of the stat after applying all modifiers, using `base` if it has none.
+    pub fn get_or(&self, stat: Stat, base: f32) -> f32 {
+        self.get(stat).unwrap_or_else(|| self.modify(stat, base))
+    }
+
+    /// Scales the damage of an attack by the character's [`Stat::AttackDamage`].
+    pub fn attack_damage(&self, damage: usize) -> usize {
+        (damage as f32 * self.get_or(Stat::AttackDamage, 1.0)).round() as usize
+    }
+
+    /// Adds modifiers from `source`, lasting for `duration` seconds or until removed.
+    /// Adding modifiers from a source again replaces the previous ones, so e.g. drinking
+    /// the same potion twice refreshes its duration instead of stacking.
+    pub fn add_modifiers(
+        &mut self,
+        source: impl Into<String>,
+        modifiers: impl IntoIterator<Item = StatModifier>,
+        duration: Option<f32>,
+    ) {
+        let source = source.into();
+        self.remove_source(&source);
+
+        self.modifiers
+            .extend(modifiers.into_iter().map(|modifier| ActiveModifier {
+                source: source.clone(),
+                modifier,
+                timer: duration.map(|seconds| Timer::from_seconds(seconds, TimerMode::Once)),
+            }));
+    }
+
+    /// Removes all modifiers from `source`.
+    pub fn remove_source(&mut self, source: &str) {
+        self.modifiers.retain(|active| active.source != source);
+    }
+
+    /// Advances the timers of timed modifiers and removes the ones which ran out. Returns
+    /// whether any were removed.
+    fn tick(&mut self, delta: Duration) -> bool {
+        let count = self.modifiers.len();
+
+        for active in &mut self.modifiers {
+            if let Some(timer) = active.timer.as_mut() {
+                timer.tick(delta);
+            }
+        }
+
+        self.modifiers
+            .retain(|active| !active.timer.as_ref().is_some_and(Timer::is_finished));
+
+        self.modifiers.len() != count
+    }
+
+    fn modify(&self, stat: Stat, base: f32) -> f32 {
+        let (added, multiplier) = self
+            .modifiers
+            .iter()
+            .filter(|active| active.modifier.stat() == stat)
+            .fold((0.0, 1.0), |(added, multiplier), active| {
+                match active.modifier {
+                    StatModifier::Add(_, value) => (added + value, multiplier),
+                    StatModifier::Multiply(_, value) => (added, multiplier * value),
+                }
+            });
+
+        ((base + added) * multiplier).max(0.0)
+    }
+}
+
+/// Adds modifiers to an entity's [`Stats`], see [`Stats::add_modifiers`].
+#[derive(EntityEvent, Debug, Clone)]
+pub struct AddModifiersEvent {
+    entity: Entity,
+    source: String,
+    modifiers: Vec<StatModifier>,
+    duration: Option<f32>,
+}
+
+impl AddModifiersEvent {
+    pub fn new(
+        entity: Entity,
+        source: impl Into<String>,
+        modifiers: Vec<StatModifier>,
+        duration: Option<f32>,
+    ) -> Self {
+        Self {
+            entity,
+            source: source.into(),
+            modifiers,
+            duration,
+        }
+    }
+}
+
+fn on_add_modifiers(event: On<AddModifiersEvent>, mut query: Query<&mut Stats>) {
+    if let Ok(mut stats) = query.get_mut(event.entity) {
+        stats.add_modifiers(
+            event.source.clone(),
+            event.modifiers.iter().copied(),
+            event.duration,
+        );
+    }
+}
+
+fn update_modifier_timers(time: Res<Time>, mut query: Query<&mut Stats>) {
+    for mut stats in &mut query {
+        // Only flag the stats as changed when a modifier actually expires.
+        if stats.bypass_change_detection().tick(time.delta()) {
+            stats.set_changed();
+        }
+    }
+}
+
+fn apply_stats(
+    mut query: Query<
+        (
+            &Stats,
+            Option<&mut Health>,
+            Option<&mut Stamina>,
+            Option<&mut MovementController>,
+        ),
+        Changed<Stats>,
+    >,
+) {
+    for (stats, health, stamina, controller) in &mut query {
+        if let Some(mut health) = health {
+            if let Some(max) = stats.get(Stat::MaxHealth) {
+                health.set_max(max.round() as usize);
+            }
+            if let Some(regen) = stats.get(Stat::HealthRegen) {
+                health.regen_per_second = regen;
+            }
+        }
+
+        if let Some(mut stamina) = stamina {
+            if let Some(max) = stats.get(Stat::MaxStamina) {
+                stamina.set_max(max.round() as usize);
+            }
+            if let Some(regen) = stats.get(Stat::StaminaRegen) {
+                stamina.regen_per_second = regen;
+            }
+        }
+
+        if let Some(mut controller) = controller
+            && let Some(speed) = stats.get(Stat::MoveSpeed)
+        {
+            controller.max_speed = speed;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn additions_apply_before_multipliers() {
+        let mut stats = Stats::default().with_base(Stat::MaxHealth, 100.0);
+        stats.add_modifiers("ring", [StatModifier::Multiply(Stat::MaxHealth, 2.0)], None);
+        stats.add_modifiers("amulet", [StatModifier::Add(Stat::MaxHealth, 50.0)], None);
+
+        assert_eq!(stats.get(Stat::MaxHealth), Some(300.0));
+        assert_eq!(stats.get(Stat::MaxStamina), None);
+        assert_eq!(stats.get_or(Stat::AttackDamage, 1.0), 1.0);
+    }
+
+    #[test]
+    fn same_source_replaces_its_modifiers() {
+        let mut stats = Stats::default().with_base(Stat::MoveSpeed, 4.0);
+        stats.add_modifiers(
+            "potion",
+            [StatModifier::Add(Stat::MoveSpeed, 1.0)],
+            Some(5.0),
+        );
+        stats.add_modifiers(
+            "potion",
+            [StatModifier::Add(Stat::MoveSpeed, 2.0)],
+            Some(5.0),
+        );
+
+        assert_eq!(stats.get(Stat::MoveSpeed), Some(6.0));
+
+        stats.remove_source("potion");
+        assert_eq!(stats.get(Stat::MoveSpeed), Some(4.0));
+    }
+
+    #[test]
+    fn timed_modifiers_expire() {
+        let mut stats = Stats::default().with_base(Stat::MoveSpeed, 4.0);
+        stats.add_modifiers(
+            "potion",
+            [StatModifier::Add(Stat::MoveSpeed, 2.0)],
+            Some(1.0),
+        );
+        stats.add_modifiers("boots", [StatModifier::Add(Stat::MoveSpeed, 1.0)], None);
+
+        assert!(!stats.tick(Duration::from_millis(500)));
+        assert_eq!(stats.get(Stat::MoveSpeed), Some(7.0));
+
+        assert!(stats.tick(Duration::from_millis(500)));
+        assert_eq!(stats.get(Stat::MoveSpeed), Some(5.0));
+
+        assert!(!stats.tick(Duration::from_secs(10)));
+        assert_eq!(stats.get(Stat::MoveSpeed), Some(5.0));
+    }
+}
@@ -0,0 +1,128 @@
+//! Chests, which drop their [`Loot`] the first time they are opened by interacting with
+//! them.
+
+use bevy::prelude::*;
+
+use crate::asset_tracking::LoadResource;
+use crate::game::grid::coords::WorldPosition;
+use crate::game::interaction::{InteractEvent, Interactable};
+use crate::game::loot::{DropLootEvent, Loot};
+use crate::game::object::Shadow;
+use crate::game::physics::components::{Collider, PhysicsData};
+
+pub(super) fn plugin(app: &mut App) {
+    app.load_resource::<ChestAssets>();
+
+    app.add_observer(on_chest_interact)
+        .add_observer(on_open_chest);
+}
+
+/// How close a player has to get to a chest to open it.
+const CHEST_INTERACT_RADIUS: f32 = 1.0;
+
+const CHEST_SIZE: Vec3 = Vec3::new(0.4, 0.3, 0.3);
+
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct Chest {
+    pub opened: bool,
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct ChestAssets {
+    #[dependency]
+    closed: Handle<Image>,
+    #[dependency]
+    open: Handle<Image>,
+    #[dependency]
+    shadow: Handle<Image>,
+}
+
+impl FromWorld for ChestAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            closed: assets.load("images/chest_closed.png"),
+            open: assets.load("images/chest_open.png"),
+            shadow: assets.load("images/chest_shadow.png"),
+        }
+    }
+}
+
+/// A closed chest which drops a roll of the loot table when opened.
+pub fn chest(
+    loot_table: impl Into<String>,
+    assets: &ChestAssets,
+    position: Vec3,
+    scale: f32,
+) -> impl Bundle {
+    let shadow = assets.shadow.clone();
+
+    (
+        Name::new("Chest"),
+        Chest::default(),
+        Loot::new(loot_table),
+        Interactable::new("Open", CHEST_INTERACT_RADIUS),
+        WorldPosition(position.into()),
+        Transform::from_scale(Vec3::splat(scale)),
+        // Physics
+        Collider::cuboid(CHEST_SIZE, position),
+        PhysicsData::Static,
+        // Rendering
+        Sprite::from(assets.closed.clone()),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Shadow,
+                Sprite {
+                    image: shadow,
+                    color: Color::srgba(1.0, 1.0, 1.0, 0.5),
+                    ..default()
+                },
+                Transform::from_translation(Vec3::new(0.0, -0.125 * scale, -0.1)),
+            ));
+        })),
+    )
+}
+
+/// Opens a [`Chest`], dropping its loot if it was still closed.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct OpenChestEvent {
+    entity: Entity,
+}
+
+impl OpenChestEvent {
+    pub fn new(entity: Entity) -> Self {
+        Self { entity }
+    }
+}
+
+fn on_chest_interact(
+    event: On<InteractEvent>,
+    query: Query<(), With<Chest>>,
+    mut commands: Commands,
+) {
+    if query.contains(event.event_target()) {
+        commands.trigger(OpenChestEvent::new(event.event_target()));
+    }
+}
+
+fn on_open_chest(
+    event: On<OpenChestEvent>,
+    mut query: Query<(&mut Chest, &mut Sprite)>,
+    assets: Res<ChestAssets>,
+    mut commands: Commands,
+) {
+    let Ok((mut chest, mut sprite)) = query.get_mut(event.entity) else {
+        return;
+    };
+
+    if chest.opened {
+        return;
+    }
+
+    chest.opened = true;
+    sprite.image = assets.open.clone();
+    commands.entity(event.entity).remove::<Interactable>();
+    commands.trigger(DropLootEvent::new(event.entity));
+}
@@ -0,0 +1,268 @@
+//! Branching conversations, defined in `.dialogue.ron` asset files.
+//!
+//! Interacting with an entity that has a [`Dialogue`] starts its conversation, which lives
+//! in the [`ActiveDialogue`] resource while the dialogue menu is open. The menu moves the
+//! conversation along with [`AdvanceDialogueEvent`] and [`ChooseDialogueEvent`].
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::Pause;
+use crate::asset_tracking::RegisterRonAsset;
+use crate::game::flags::{FlagCondition, GameFlags, SetFlagEvent};
+use crate::game::interaction::InteractEvent;
+use crate::game::item::ItemId;
+use crate::game::item::inventory::{InventoryAction, InventoryEvent};
+use crate::game::quest::StartQuestEvent;
+use crate::menus::Menu;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ron_asset::<DialogueTree>(&["dialogue.ron"]);
+
+    app.add_systems(OnExit(Menu::Dialogue), end_dialogue)
+        .add_observer(on_dialogue_interact)
+        .add_observer(on_advance_dialogue)
+        .add_observer(on_choose_dialogue);
+}
+
+/// A whole conversation, as written in an asset file.
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct DialogueTree {
+    /// The node the conversation starts at.
+    pub start: String,
+    pub nodes: Vec<DialogueNode>,
+}
+
+impl DialogueTree {
+    pub fn get(&self, id: &str) -> Option<&DialogueNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DialogueNode {
+    pub id: String,
+    pub speaker: String,
+    /// Asset path of the speaker's portrait.
+    #[serde(default)]
+    pub portrait: Option<String>,
+    /// Shown one after another, before the choices.
+    pub lines: Vec<String>,
+    /// Offered after the last line. Without any available, the conversation moves on to
+    /// `next`.
+    #[serde(default)]
+    pub choices: Vec<DialogueChoice>,
+    /// Where to go after the last line if there are no available choices, or the end.
+    #[serde(default)]
+    pub next: Option<String>,
+    /// Run when the conversation reaches this node.
+    #[serde(default)]
+    pub actions: Vec<DialogueAction>,
+}
+
+impl DialogueNode {
+    /// Whether any choice can be picked, or the conversation just continues instead.
+    pub fn has_available_choice(&self, flags: &GameFlags) -> bool {
+        self.choices.iter().any(|choice| choice.is_available(flags))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DialogueChoice {
+    pub text: String,
+    /// Where choosing this goes, or the end.
+    #[serde(default)]
+    pub next: Option<String>,
+    /// The choice is only offered if all of these are met.
+    #[serde(default)]
+    pub conditions: Vec<FlagCondition>,
+    /// Run when the choice is picked.
+    #[serde(default)]
+    pub actions: Vec<DialogueAction>,
+}
+
+impl DialogueChoice {
+    pub fn is_available(&self, flags: &GameFlags) -> bool {
+        self.conditions
+            .iter()
+            .all(|condition| condition.is_met(flags))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum DialogueAction {
+    SetFlag(String),
+    ClearFlag(String),
+    /// Gives items to whoever started the conversation. Whatever doesn't fit into their
+    /// inventory is dropped at their feet.
+    GiveItem(ItemId, u32),
+    StartQuest(String),
+}
+
+/// The conversation an entity starts when interacted with.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Dialogue(pub Handle<DialogueTree>);
+
+/// The conversation that is currently going on.
+#[derive(Resource, Debug, Clone)]
+pub struct ActiveDialogue {
+    pub tree: Handle<DialogueTree>,
+    pub node: String,
+    /// Index of the current line in the node.
+    pub line: usize,
+    /// Who started the conversation.
+    pub player: Entity,
+}
+
+impl ActiveDialogue {
+    pub fn is_last_line(&self, node: &DialogueNode) -> bool {
+        self.line + 1 >= node.lines.len()
+    }
+}
+
+/// Moves on to the next line, or past the current node if it has no choices.
+#[derive(Event, Debug, Clone)]
+pub struct AdvanceDialogueEvent;
+
+/// Picks the choice with this index in the current node.
+#[derive(Event, Debug, Clone)]
+pub struct ChooseDialogueEvent(pub usize);
+
+fn on_dialogue_interact(
+    event: On<InteractEvent>,
+    query: Query<&Dialogue>,
+    trees: Res<Assets<DialogueTree>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut commands: Commands,
+) {
+    let Ok(Dialogue(handle)) = query.get(event.event_target()) else {
+        return;
+    };
+
+    let Some(tree) = trees.get(handle) else {
+        return;
+    };
+
+    let mut dialogue = ActiveDialogue {
+        tree: handle.clone(),
+        node: String::new(),
+        line: 0,
+        player: event.interactor,
+    };
+
+    if enter_node(&mut dialogue, tree, Some(&tree.start), &mut commands) {
+        commands.insert_resource(dialogue);
+        next_menu.set(Menu::Dialogue);
+        next_pause.set(Pause(true));
+    }
+}
+
+fn on_advance_dialogue(
+    _: On<AdvanceDialogueEvent>,
+    dialogue: Option<ResMut<ActiveDialogue>>,
+    trees: Res<Assets<DialogueTree>>,
+    flags: Res<GameFlags>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut commands: Commands,
+) {
+    let Some(mut dialogue) = dialogue else {
+        return;
+    };
+
+    let Some(tree) = trees.get(&dialogue.tree) else {
+        return;
+    };
+
+    let Some(node) = tree.get(&dialogue.node) else {
+        return;
+    };
+
+    if !dialogue.is_last_line(node) {
+        dialogue.line += 1;
+    } else if !node.has_available_choice(&flags)
+        && !enter_node(&mut dialogue, tree, node.next.as_ref(), &mut commands)
+    {
+        next_menu.set(Menu::None);
+    }
+}
+
+fn on_choose_dialogue(
+    event: On<ChooseDialogueEvent>,
+    dialogue: Option<ResMut<ActiveDialogue>>,
+    trees: Res<Assets<DialogueTree>>,
+    flags: Res<GameFlags>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut commands: Commands,
+) {
+    let Some(mut dialogue) = dialogue else {
+        return;
+    };
+
+    let Some(tree) = trees.get(&dialogue.tree) else {
+        return;
+    };
+
+    let Some(choice) = tree
+        .get(&dialogue.node)
+        .and_then(|node| node.choices.get(event.0))
+        .filter(|choice| choice.is_available(&flags))
+    else {
+        return;
+    };
+
+    run_actions(&choice.actions, dialogue.player, &mut commands);
+
+    if !enter_node(&mut dialogue, tree, choice.next.as_ref(), &mut commands) {
+        next_menu.set(Menu::None);
+    }
+}
+
+/// Moves the conversation to the node and runs its actions. Returns false if the
+/// conversation is over instead.
+fn enter_node(
+    dialogue: &mut ActiveDialogue,
+    tree: &DialogueTree,
+    id: Option<&String>,
+    commands: &mut Commands,
+) -> bool {
+    let Some(id) = id else {
+        return false;
+    };
+
+    let Some(node) = tree.get(id) else {
+        warn!("No dialogue node with id {id}");
+        return false;
+    };
+
+    dialogue.node = id.clone();
+    dialogue.line = 0;
+    run_actions(&node.actions, dialogue.player, commands);
+
+    true
+}
+
+fn run_actions(actions: &[DialogueAction], player: Entity, commands: &mut Commands) {
+    for action in actions {
+        match action {
+            DialogueAction::SetFlag(flag) => commands.trigger(SetFlagEvent {
+                flag: flag.clone(),
+                value: true,
+            }),
+            DialogueAction::ClearFlag(flag) => commands.trigger(SetFlagEvent {
+                flag: flag.clone(),
+                value: false,
+            }),
+            DialogueAction::GiveItem(item, count) => commands.trigger(InventoryEvent::new(
+                player,
+                InventoryAction::Add(item.clone(), *count),
+            )),
+            DialogueAction::StartQuest(quest) => commands.trigger(StartQuestEvent(quest.clone())),
+        }
+    }
+}
+
+fn end_dialogue(mut commands: Commands) {
+    commands.remove_resource::<ActiveDialogue>();
+}
@@ -0,0 +1,74 @@
+//! Named flags recording what has happened in the game, e.g. `"met_ducky"`.
+//!
+//! Flags are changed with a [`SetFlagEvent`]. Everything else reads them from
+//! [`GameFlags`].
+
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<GameFlags>();
+
+    app.add_observer(on_set_flag);
+}
+
+/// Only holds plain data, so it can be saved and loaded along with the [`QuestLog`].
+///
+/// [`QuestLog`]: crate::game::quest::QuestLog
+#[derive(Resource, Debug, Clone, Default, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct GameFlags(HashSet<String>);
+
+impl GameFlags {
+    pub fn is_set(&self, flag: &str) -> bool {
+        self.0.contains(flag)
+    }
+}
+
+/// A check against [`GameFlags`], e.g. to only offer a dialogue choice once.
+#[derive(Debug, Clone, PartialEq, Eq, Reflect, Deserialize)]
+pub enum FlagCondition {
+    Set(String),
+    NotSet(String),
+}
+
+impl FlagCondition {
+    pub fn is_met(&self, flags: &GameFlags) -> bool {
+        match self {
+            FlagCondition::Set(flag) => flags.is_set(flag),
+            FlagCondition::NotSet(flag) => !flags.is_set(flag),
+        }
+    }
+}
+
+/// Sets or clears a flag.
+#[derive(Event, Debug, Clone)]
+pub struct SetFlagEvent {
+    pub flag: String,
+    pub value: bool,
+}
+
+fn on_set_flag(event: On<SetFlagEvent>, mut flags: ResMut<GameFlags>) {
+    if event.value {
+        flags.0.insert(event.flag.clone());
+    } else {
+        flags.0.remove(&event.flag);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_round_trip() {
+        let flags = GameFlags(HashSet::from_iter(["met_ducky".to_string()]));
+
+        let saved = ron::to_string(&flags).unwrap();
+        let loaded: GameFlags = ron::from_str(&saved).unwrap();
+
+        assert_eq!(loaded, flags);
+        assert!(loaded.is_set("met_ducky"));
+    }
+}
@@ -203,8 +203,45 @@ pub fn rotate_screen_space_to_facing(screen_space: Vec2) -> Vec2 {
     rotation * (screen_space * Vec2::new(1.0, -1.0))
 }
 
+/// Converts an offset on screen, in unscaled pixels, to the offset on the ground plane
+/// (x and z) that projects to it. The inverse of [`ScreenCoords`] at a fixed height.
+pub fn screen_offset_to_ground(offset: Vec2) -> Vec2 {
+    let x_minus_z = offset.x / (TILE_WIDTH as f32 / 2.0);
+    let x_plus_z = -offset.y / (TILE_HEIGHT as f32 / 2.0);
+
+    Vec2::new(x_plus_z + x_minus_z, x_plus_z - x_minus_z) / 2.0
+}
+
 pub fn rotate_screen_space_to_movement(screen_space: Vec3) -> Vec3 {
     let angle = std::f32::consts::FRAC_PI_4;
     let rotation = Quat::from_rotation_y(angle);
     rotation * screen_space
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn screen_offset_to_ground_inverts_screen_coords() {
+        let origin = Vec3::new(1.0, 2.0, -3.0);
+        let origin_screen = ScreenCoords::from(WorldCoords(origin));
+
+        for ground in [
+            Vec2::new(1.0, 0.0),
+            Vec2::new(0.0, 1.0),
+            Vec2::new(-2.5, 4.0),
+            Vec2::new(3.25, -1.75),
+        ] {
+            let world = origin + Vec3::new(ground.x, 0.0, ground.y);
+            let screen = ScreenCoords::from(WorldCoords(world));
+            let offset = (screen.0 - origin_screen.0).xy();
+
+            let result = screen_offset_to_ground(offset);
+            assert!(
+                result.abs_diff_eq(ground, 1e-4),
+                "{ground} came back as {result}"
+            );
+        }
+    }
+}
@@ -1,12 +1,13 @@
 use crate::game::character::player::Player;
 use crate::game::grid::coords::{SCREEN_Z_SCALE, TileCoords, TilePosition, WorldPosition};
 pub(crate) use crate::game::grid::tile::TileAssets;
+use crate::game::grid::tile::TileType;
 use crate::game::object::Shadow;
 use bevy::prelude::*;
 use std::collections::BTreeMap;
-use std::sync::{Arc, RwLock};
 
 pub mod coords;
+pub mod pathfinding;
 pub mod tile;
 
 pub(super) fn plugin(app: &mut App) {
@@ -27,8 +28,13 @@ fn hide_objects_above(
     mut child_query: Query<(Entity, &mut Sprite), Without<WorldPosition>>,
     player_query: Query<&WorldPosition, With<Player>>,
 ) {
-    let player_height = if let Ok(player_pos) = player_query.single() {
-        player_pos.0.y
+    // Clear the view for the lowest player, so nobody is hidden in co-op.
+    let player_height = if let Some(height) = player_query
+        .iter()
+        .map(|player_pos| player_pos.0.y)
+        .reduce(f32::min)
+    {
+        height
     } else {
         return;
     };
@@ -72,8 +78,13 @@ fn hide_tiles_above(
     mut child_query: Query<(Entity, &mut Sprite), Without<TilePosition>>,
     player_query: Query<&WorldPosition, With<Player>>,
 ) {
-    let player_height = if let Ok(player_pos) = player_query.single() {
-        player_pos.0.y
+    // Clear the view for the lowest player, so nobody is hidden in co-op.
+    let player_height = if let Some(height) = player_query
+        .iter()
+        .map(|player_pos| player_pos.0.y)
+        .reduce(f32::min)
+    {
+        height
     } else {
         return;
     };
@@ -128,10 +139,38 @@ fn correct_shadow_opacity(mut query: Query<&mut Sprite, With<Shadow>>) {
 #[derive(Component)]
 pub struct Grid;
 
-pub fn grid(_tile_map: Arc<RwLock<BTreeMap<TileCoords, Entity>>>, scale: f32) -> impl Bundle {
+pub fn grid(scale: f32) -> impl Bundle {
     (
         Grid,
         Transform::from_scale(Vec2::splat(scale).extend(SCREEN_Z_SCALE)),
         InheritedVisibility::default(),
     )
 }
+
+/// Every tile in the current level, keyed by its coordinates.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TileMap {
+    tiles: BTreeMap<TileCoords, TileType>,
+}
+
+impl TileMap {
+    pub fn insert(&mut self, coords: impl Into<TileCoords>, tile_type: TileType) {
+        self.tiles.insert(coords.into(), tile_type);
+    }
+
+    pub fn get(&self, coords: &TileCoords) -> Option<&TileType> {
+        self.tiles.get(coords)
+    }
+
+    pub fn contains(&self, coords: &TileCoords) -> bool {
+        self.tiles.contains_key(coords)
+    }
+}
+
+impl FromIterator<(TileCoords, TileType)> for TileMap {
+    fn from_iter<T: IntoIterator<Item = (TileCoords, TileType)>>(iter: T) -> Self {
+        Self {
+            tiles: iter.into_iter().collect(),
+        }
+    }
+}
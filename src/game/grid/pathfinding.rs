@@ -0,0 +1,488 @@
+//! A* pathfinding over the walkable tops of tiles in the [`TileMap`].
+//!
+//! Every tile whose top is exposed is a node. Flat tiles (full, layer and
+//! bridge tiles) have a single surface height, while ramps (stairs and
+//! slopes) rise towards their facing, so the height a character stands at
+//! depends on which edge of the ramp it enters from. Two neighbouring nodes
+//! are connected when the heights of their shared edge differ by no more than
+//! [`STEP_UP_HEIGHT`], which is the same rule the character controller uses
+//! when stepping up ledges.
+
+use bevy::prelude::*;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+use crate::game::grid::TileMap;
+use crate::game::grid::coords::TileCoords;
+use crate::game::grid::tile::{TileFacing, TileType};
+use crate::game::physics::movement::STEP_UP_HEIGHT;
+
+/// Number of empty tiles needed above a surface for a character to stand on it.
+const CLEARANCE_TILES: i32 = 2;
+
+/// Height of a character's [`WorldPosition`](crate::game::grid::coords::WorldPosition)
+/// above the surface it is standing on.
+const STANDING_HEIGHT: f32 = 0.5;
+
+/// Upper bound on how many nodes a single search will expand.
+const MAX_EXPANDED_NODES: usize = 4096;
+
+const CARDINALS: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+
+/// How close a character needs to get to a waypoint, horizontally, before moving on to the next.
+const WAYPOINT_RADIUS: f32 = 0.2;
+
+/// A world-space path for a character to follow.
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct NavPath {
+    waypoints: VecDeque<Vec3>,
+}
+
+impl NavPath {
+    /// Creates a path from the output of [`find_path`]. The first waypoint is the tile the
+    /// character is already standing on, so it is skipped.
+    pub fn new(waypoints: Vec<Vec3>) -> Self {
+        let mut waypoints = VecDeque::from(waypoints);
+        if waypoints.len() > 1 {
+            waypoints.pop_front();
+        }
+
+        Self { waypoints }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.waypoints.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.waypoints.clear();
+    }
+
+    /// The direction to move in to follow the path from `position`, suitable for a
+    /// [`MovementController`](crate::game::physics::movement::MovementController) intent.
+    /// Waypoints are dropped as they are reached, and once the path is finished this
+    /// returns [`Vec3::ZERO`].
+    pub fn steer(&mut self, position: Vec3) -> Vec3 {
+        while let Some(waypoint) = self.waypoints.front() {
+            let offset = (*waypoint - position).xz();
+
+            if offset.length() > WAYPOINT_RADIUS {
+                return Vec3::new(offset.x, 0.0, offset.y).normalize_or_zero();
+            }
+
+            self.waypoints.pop_front();
+        }
+
+        Vec3::ZERO
+    }
+}
+
+/// Finds a path between two world positions, returning the world-space
+/// waypoints a character should walk through, ending at the goal's tile.
+///
+/// Returns `None` if either position is not above a walkable tile or if the
+/// goal cannot be reached.
+pub fn find_path(tile_map: &TileMap, start: Vec3, goal: Vec3) -> Option<Vec<Vec3>> {
+    let start = surface_below(tile_map, start)?;
+    let goal = surface_below(tile_map, goal)?;
+
+    find_tile_path(tile_map, &start, &goal).map(|tiles| {
+        tiles
+            .iter()
+            .map(|coords| standing_position(tile_map, coords))
+            .collect()
+    })
+}
+
+/// Finds a path of walkable tiles between two walkable tiles, including both ends.
+pub fn find_tile_path(
+    tile_map: &TileMap,
+    start: &TileCoords,
+    goal: &TileCoords,
+) -> Option<Vec<TileCoords>> {
+    if !is_walkable(tile_map, start) || !is_walkable(tile_map, goal) {
+        return None;
+    }
+
+    let mut open = BinaryHeap::new();
+    let mut came_from = HashMap::<IVec3, IVec3>::new();
+    let mut cost_so_far = HashMap::<IVec3, f32>::new();
+
+    open.push(OpenNode {
+        coords: start.0,
+        priority: heuristic(start.0, goal.0),
+    });
+    cost_so_far.insert(start.0, 0.0);
+
+    let mut expanded = 0;
+
+    while let Some(OpenNode { coords, .. }) = open.pop() {
+        if coords == goal.0 {
+            let mut path = vec![TileCoords(coords)];
+            let mut current = coords;
+            while let Some(previous) = came_from.get(&current) {
+                path.push(TileCoords(*previous));
+                current = *previous;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        expanded += 1;
+        if expanded > MAX_EXPANDED_NODES {
+            return None;
+        }
+
+        let current_cost = cost_so_far[&coords];
+
+        for (neighbour, step_cost) in neighbours(tile_map, &TileCoords(coords)) {
+            let new_cost = current_cost + step_cost;
+
+            if cost_so_far
+                .get(&neighbour.0)
+                .is_none_or(|&cost| new_cost < cost)
+            {
+                cost_so_far.insert(neighbour.0, new_cost);
+                came_from.insert(neighbour.0, coords);
+                open.push(OpenNode {
+                    coords: neighbour.0,
+                    priority: new_cost + heuristic(neighbour.0, goal.0),
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// The walkable tile whose surface is closest below the given world position.
+pub fn surface_below(tile_map: &TileMap, position: Vec3) -> Option<TileCoords> {
+    let column = IVec2::new(position.x.round() as i32, position.z.round() as i32);
+    let feet = position.y - STANDING_HEIGHT;
+
+    // Tiles whose surface can be at or below the feet, from the top down. Ramps sit half a
+    // tile lower than flat tiles, so look one tile higher than the feet to find them.
+    let top = (feet + 1.0).floor() as i32;
+
+    (top - 8..=top)
+        .rev()
+        .map(|y| TileCoords(IVec3::new(column.x, y, column.y)))
+        .find(|coords| {
+            is_walkable(tile_map, coords)
+                && surface_height(tile_map, coords).is_some_and(|height| height <= feet + 0.5)
+        })
+}
+
+/// The world position of a character standing in the middle of a walkable tile.
+pub fn standing_position(tile_map: &TileMap, coords: &TileCoords) -> Vec3 {
+    let height = surface_height(tile_map, coords).unwrap_or(coords.y as f32 + 0.5);
+    Vec3::new(coords.x as f32, height + STANDING_HEIGHT, coords.z as f32)
+}
+
+/// Whether a character can stand on top of the tile at the given coordinates.
+pub fn is_walkable(tile_map: &TileMap, coords: &TileCoords) -> bool {
+    tile_map.get(coords).is_some()
+        && (1..=CLEARANCE_TILES).all(|dy| !tile_map.contains(&TileCoords(coords.0 + IVec3::Y * dy)))
+}
+
+/// Height of the surface in the middle of the tile.
+fn surface_height(tile_map: &TileMap, coords: &TileCoords) -> Option<f32> {
+    let y = coords.y as f32;
+
+    tile_map.get(coords).map(|tile_type| match tile_type {
+        TileType::Full { .. } | TileType::Layer { .. } | TileType::Bridge { .. } => y + 0.5,
+        TileType::Stairs(_) => y,
+        TileType::SlopeLower { .. } => y - 0.25,
+        TileType::SlopeUpper { .. } => y + 0.25,
+    })
+}
+
+/// Height of the surface where the tile meets its neighbour in `direction`.
+fn edge_height(tile_map: &TileMap, coords: &TileCoords, direction: IVec2) -> Option<f32> {
+    let y = coords.y as f32;
+
+    let (low, high, facing) = match tile_map.get(coords)? {
+        TileType::Full { .. } | TileType::Layer { .. } | TileType::Bridge { .. } => {
+            return Some(y + 0.5);
+        }
+        TileType::Stairs(facing) => (y - 0.5, y + 0.5, facing),
+        TileType::SlopeLower { facing, .. } => (y - 0.5, y, facing),
+        TileType::SlopeUpper { facing, .. } => (y, y + 0.5, facing),
+    };
+
+    let uphill = facing_direction(facing);
+
+    Some(if direction == uphill {
+        high
+    } else if direction == -uphill {
+        low
+    } else {
+        (low + high) / 2.0
+    })
+}
+
+fn facing_direction(facing: &TileFacing) -> IVec2 {
+    match facing {
+        TileFacing::PosX => IVec2::X,
+        TileFacing::NegX => IVec2::NEG_X,
+        TileFacing::PosZ => IVec2::Y,
+        TileFacing::NegZ => IVec2::NEG_Y,
+    }
+}
+
+/// The walkable tile reached by stepping from `coords` in a cardinal `direction`, if any.
+fn step(tile_map: &TileMap, coords: &TileCoords, direction: IVec2) -> Option<TileCoords> {
+    let exit_height = edge_height(tile_map, coords, direction)?;
+
+    [0, 1, -1].into_iter().find_map(|dy| {
+        let candidate = TileCoords(coords.0 + IVec3::new(direction.x, dy, direction.y));
+
+        if !is_walkable(tile_map, &candidate) {
+            return None;
+        }
+
+        let entry_height = edge_height(tile_map, &candidate, -direction)?;
+
+        ((entry_height - exit_height).abs() <= STEP_UP_HEIGHT).then_some(candidate)
+    })
+}
+
+/// Walkable neighbours of a tile along with the cost of moving to them.
+///
+/// Diagonal moves are only allowed when both of the cardinal routes around the corner are
+/// walkable and lead to the same tile, so paths never cut across ledges or walls.
+fn neighbours(tile_map: &TileMap, coords: &TileCoords) -> Vec<(TileCoords, f32)> {
+    let mut neighbours = CARDINALS
+        .iter()
+        .filter_map(|&direction| {
+            step(tile_map, coords, direction).map(|next| {
+                let cost = (standing_position(tile_map, &next)
+                    - standing_position(tile_map, coords))
+                .length();
+                (next, cost)
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for (a, b) in [
+        (IVec2::X, IVec2::Y),
+        (IVec2::Y, IVec2::NEG_X),
+        (IVec2::NEG_X, IVec2::NEG_Y),
+        (IVec2::NEG_Y, IVec2::X),
+    ] {
+        let via_a = step(tile_map, coords, a).and_then(|next| step(tile_map, &next, b));
+        let via_b = step(tile_map, coords, b).and_then(|next| step(tile_map, &next, a));
+
+        if let (Some(via_a), Some(via_b)) = (via_a, via_b)
+            && via_a == via_b
+        {
+            let cost = (standing_position(tile_map, &via_a) - standing_position(tile_map, coords))
+                .length();
+            neighbours.push((via_a, cost));
+        }
+    }
+
+    neighbours
+}
+
+/// Horizontal distance between two tiles. Surface heights don't follow tile heights exactly,
+/// so the vertical component is left out to keep the estimate from overshooting.
+fn heuristic(from: IVec3, to: IVec3) -> f32 {
+    from.xz().as_vec2().distance(to.xz().as_vec2())
+}
+
+/// An entry in the A* open set, ordered so the [`BinaryHeap`] pops the lowest priority first.
+struct OpenNode {
+    coords: IVec3,
+    priority: f32,
+}
+
+impl PartialEq for OpenNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl Eq for OpenNode {}
+
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.total_cmp(&self.priority)
+    }
+}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::grid::tile::TileEdges;
+
+    /// A flat floor at `y = 0` covering the given ranges of `x` and `z`.
+    fn floor(xs: std::ops::RangeInclusive<i32>, zs: std::ops::RangeInclusive<i32>) -> TileMap {
+        xs.flat_map(|x| zs.clone().map(move |z| (x, z)))
+            .map(|(x, z)| (TileCoords(IVec3::new(x, 0, z)), TileType::default()))
+            .collect()
+    }
+
+    fn path(tile_map: &TileMap, start: IVec3, goal: IVec3) -> Option<Vec<IVec3>> {
+        find_tile_path(tile_map, &TileCoords(start), &TileCoords(goal))
+            .map(|path| path.into_iter().map(|coords| coords.0).collect())
+    }
+
+    #[test]
+    fn straight_path() {
+        let tile_map = floor(0..=4, 0..=0);
+
+        let path = path(&tile_map, IVec3::ZERO, IVec3::new(4, 0, 0)).unwrap();
+
+        assert_eq!(
+            path,
+            (0..=4).map(|x| IVec3::new(x, 0, 0)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn path_goes_around_walls() {
+        let mut tile_map = floor(0..=4, 0..=2);
+        tile_map.insert(IVec3::new(2, 1, 0), TileType::default());
+        tile_map.insert(IVec3::new(2, 1, 1), TileType::default());
+
+        let path = path(&tile_map, IVec3::ZERO, IVec3::new(4, 0, 0)).unwrap();
+
+        assert!(path.contains(&IVec3::new(2, 0, 2)));
+        assert!(path.iter().all(|coords| coords.y == 0));
+        assert!(!path.iter().any(|coords| coords.x == 2 && coords.z < 2));
+    }
+
+    #[test]
+    fn stairs_lead_up_a_level() {
+        let mut tile_map = floor(0..=1, 0..=0);
+        tile_map.insert(IVec3::new(2, 1, 0), TileType::Stairs(TileFacing::PosX));
+        tile_map.insert(IVec3::new(3, 1, 0), TileType::default());
+        tile_map.insert(IVec3::new(4, 1, 0), TileType::default());
+
+        let path = path(&tile_map, IVec3::ZERO, IVec3::new(4, 1, 0)).unwrap();
+
+        assert_eq!(
+            path,
+            vec![
+                IVec3::new(0, 0, 0),
+                IVec3::new(1, 0, 0),
+                IVec3::new(2, 1, 0),
+                IVec3::new(3, 1, 0),
+                IVec3::new(4, 1, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn slopes_lead_up_and_down_a_level() {
+        let mut tile_map = floor(0..=1, 0..=0);
+        tile_map.insert(
+            IVec3::new(2, 1, 0),
+            TileType::SlopeLower {
+                facing: TileFacing::PosX,
+                has_edge: false,
+            },
+        );
+        tile_map.insert(
+            IVec3::new(3, 1, 0),
+            TileType::SlopeUpper {
+                facing: TileFacing::PosX,
+                has_edge: false,
+            },
+        );
+        tile_map.insert(IVec3::new(4, 1, 0), TileType::default());
+
+        let expected = vec![
+            IVec3::new(0, 0, 0),
+            IVec3::new(1, 0, 0),
+            IVec3::new(2, 1, 0),
+            IVec3::new(3, 1, 0),
+            IVec3::new(4, 1, 0),
+        ];
+
+        assert_eq!(
+            path(&tile_map, IVec3::ZERO, IVec3::new(4, 1, 0)).unwrap(),
+            expected
+        );
+        assert_eq!(
+            path(&tile_map, IVec3::new(4, 1, 0), IVec3::ZERO).unwrap(),
+            expected.into_iter().rev().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn slopes_are_too_steep_to_enter_from_the_high_side() {
+        let slope = |facing| {
+            let mut tile_map = floor(0..=1, 0..=0);
+            tile_map.insert(
+                IVec3::new(2, 1, 0),
+                TileType::SlopeLower {
+                    facing,
+                    has_edge: false,
+                },
+            );
+            tile_map
+        };
+
+        // Facing away from the floor, the low edge meets it.
+        let low_side = slope(TileFacing::PosX);
+        assert!(path(&low_side, IVec3::ZERO, IVec3::new(2, 1, 0)).is_some());
+
+        // Facing the floor, the high edge is a whole step above it.
+        let high_side = slope(TileFacing::NegX);
+        let floor_edge = edge_height(&high_side, &TileCoords(IVec3::new(1, 0, 0)), IVec2::X);
+        let slope_edge = edge_height(&high_side, &TileCoords(IVec3::new(2, 1, 0)), IVec2::NEG_X);
+        assert!(slope_edge.unwrap() - floor_edge.unwrap() >= STEP_UP_HEIGHT);
+        assert_eq!(path(&high_side, IVec3::ZERO, IVec3::new(2, 1, 0)), None);
+    }
+
+    #[test]
+    fn paths_go_under_and_over_bridges() {
+        let mut tile_map = floor(0..=4, 0..=4);
+        for x in 0..=4 {
+            tile_map.insert(
+                IVec3::new(x, 3, 2),
+                TileType::Bridge {
+                    facing: Some(TileFacing::PosX),
+                    edges: TileEdges::default(),
+                },
+            );
+        }
+
+        let under = path(&tile_map, IVec3::new(2, 0, 0), IVec3::new(2, 0, 4)).unwrap();
+        assert!(under.contains(&IVec3::new(2, 0, 2)));
+        assert!(under.iter().all(|coords| coords.y == 0));
+
+        let over = path(&tile_map, IVec3::new(0, 3, 2), IVec3::new(4, 3, 2)).unwrap();
+        assert_eq!(
+            over,
+            (0..=4).map(|x| IVec3::new(x, 3, 2)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn ledges_are_too_high_to_step_up() {
+        let mut tile_map = floor(0..=1, 0..=0);
+        tile_map.insert(IVec3::new(2, 1, 0), TileType::default());
+        tile_map.insert(IVec3::new(3, 1, 0), TileType::default());
+
+        assert_eq!(path(&tile_map, IVec3::ZERO, IVec3::new(3, 1, 0)), None);
+    }
+
+    #[test]
+    fn unreachable_target() {
+        let mut tile_map = floor(0..=1, 0..=0);
+        tile_map.insert(IVec3::new(4, 0, 0), TileType::default());
+
+        assert_eq!(path(&tile_map, IVec3::ZERO, IVec3::new(4, 0, 0)), None);
+        assert_eq!(path(&tile_map, IVec3::ZERO, IVec3::new(9, 0, 0)), None);
+    }
+}
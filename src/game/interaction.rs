@@ -0,0 +1,185 @@
+//! Interacting with things in the world, like chests, doors, signs or NPCs.
+//!
+//! Each [`Interactor`] focuses the closest [`Interactable`] in range that it is facing,
+//! which shows its prompt above it. Pressing interact on the interactor's own
+//! [`ActionState`] then triggers an [`InteractEvent`] on the focused entity, and everything
+//! else is up to that entity's observers.
+
+use bevy::prelude::*;
+
+use crate::game::character::Facing;
+use crate::game::grid::coords::WorldPosition;
+use crate::input::{Action, ActionState, InputMap};
+use crate::screens::Screen;
+use crate::{AppSystems, PausableSystems, Scale};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            record_interact_input.in_set(AppSystems::RecordInput),
+            update_interaction_focus.in_set(AppSystems::Update),
+            (spawn_prompts, despawn_prompts, update_prompts)
+                .chain()
+                .in_set(AppSystems::Respond),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+/// Cosine of the half-angle in front of an interactor that it can interact within.
+const FACING_ARC_COS: f32 = 0.5;
+/// Interactables this close can be used whichever way the interactor is facing.
+const FACING_IGNORE_DISTANCE: f32 = 0.4;
+
+/// Height of the prompt above the interactable, in pixels before scaling.
+const PROMPT_HEIGHT: f32 = 20.0;
+/// Keeps the prompt in front of everything else in the world.
+const PROMPT_Z: f32 = 500.0;
+
+/// Something that can be interacted with by getting close to it and pressing interact.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Interactable {
+    /// How close an interactor has to be, in world units.
+    pub radius: f32,
+    /// What interacting does, e.g. "Open" or "Talk".
+    pub prompt: String,
+}
+
+impl Interactable {
+    pub fn new(prompt: impl Into<String>, radius: f32) -> Self {
+        Self {
+            radius,
+            prompt: prompt.into(),
+        }
+    }
+}
+
+/// A character that can interact with [`Interactable`]s, when its own [`ActionState`] says
+/// so.
+#[derive(Component, Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct Interactor {
+    /// The interactable that interacting would use right now.
+    pub focus: Option<Entity>,
+}
+
+/// Triggered on an [`Interactable`] when an [`Interactor`] interacts with it.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct InteractEvent {
+    entity: Entity,
+    pub interactor: Entity,
+}
+
+/// Shows the prompt of the interactable the interactor entity is focusing.
+#[derive(Component, Debug, Clone, Copy)]
+struct InteractionPrompt(Entity);
+
+/// Adds a prompt for each newly spawned interactor.
+fn spawn_prompts(interactor_query: Query<Entity, Added<Interactor>>, mut commands: Commands) {
+    for interactor in &interactor_query {
+        commands.spawn((
+            Name::new("Interaction Prompt"),
+            InteractionPrompt(interactor),
+            Text2d::default(),
+            TextFont::from_font_size(24.0),
+            TextColor(Color::WHITE),
+            Visibility::Hidden,
+            DespawnOnExit(Screen::Gameplay),
+        ));
+    }
+}
+
+/// Removes the prompts of interactors that are gone.
+fn despawn_prompts(
+    mut removed: RemovedComponents<Interactor>,
+    prompt_query: Query<(Entity, &InteractionPrompt)>,
+    mut commands: Commands,
+) {
+    for interactor in removed.read() {
+        for (prompt, InteractionPrompt(owner)) in &prompt_query {
+            if *owner == interactor {
+                commands.entity(prompt).despawn();
+            }
+        }
+    }
+}
+
+fn record_interact_input(
+    interactor_query: Query<(Entity, &Interactor, &ActionState)>,
+    mut commands: Commands,
+) {
+    for (interactor, Interactor { focus }, action_state) in &interactor_query {
+        if action_state.just_pressed(Action::Interact)
+            && let Some(focus) = focus
+        {
+            commands.trigger(InteractEvent {
+                entity: *focus,
+                interactor,
+            });
+        }
+    }
+}
+
+fn update_interaction_focus(
+    mut interactor_query: Query<(&mut Interactor, &WorldPosition, &Facing)>,
+    interactable_query: Query<(Entity, &Interactable, &WorldPosition)>,
+) {
+    for (mut interactor, position, facing) in &mut interactor_query {
+        let focus = interactable_query
+            .iter()
+            .filter_map(|(entity, interactable, interactable_position)| {
+                let offset = interactable_position.as_vec3() - position.as_vec3();
+                let distance = offset.length();
+                let facing_it = distance <= FACING_IGNORE_DISTANCE
+                    || offset.xz().normalize_or_zero().dot(facing.direction()) >= FACING_ARC_COS;
+
+                (distance <= interactable.radius && facing_it).then_some((entity, distance))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(entity, _)| entity);
+
+        // Only flag a change when the focus actually moves.
+        interactor.set_if_neq(Interactor { focus });
+    }
+}
+
+fn update_prompts(
+    scale: Res<Scale>,
+    input_map: Res<InputMap>,
+    interactor_query: Query<(&Interactor, &ActionState)>,
+    interactable_query: Query<(&Interactable, &Transform), Without<InteractionPrompt>>,
+    mut prompt_query: Query<(
+        &InteractionPrompt,
+        &mut Text2d,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+) {
+    for (InteractionPrompt(interactor), mut text, mut transform, mut visibility) in
+        &mut prompt_query
+    {
+        // The prompt is despawned along with its interactor.
+        let Ok((interactor, action_state)) = interactor_query.get(*interactor) else {
+            continue;
+        };
+
+        let Some((interactable, interactable_transform)) = interactor
+            .focus
+            .and_then(|focus| interactable_query.get(focus).ok())
+        else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        text.0 = match input_map.prompt(Action::Interact, action_state.last_device) {
+            Some(binding) => format!("[{binding}] {}", interactable.prompt),
+            None => interactable.prompt.clone(),
+        };
+        transform.translation =
+            interactable_transform.translation.with_z(PROMPT_Z) + Vec3::Y * PROMPT_HEIGHT * scale.0;
+        *visibility = Visibility::Visible;
+    }
+}
@@ -0,0 +1,359 @@
+//! Equipment slots, which turn items into a character's weapon, armor and trinket.
+//!
+//! Whenever a character's [`Equipment`] changes, the weapon is handed to its [`Combo`],
+//! armor resistances are collected into its [`Resistances`] and the stat modifiers of all
+//! equipped items are applied to its [`Stats`].
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::AppSystems;
+use crate::game::character::combo::Combo;
+use crate::game::character::health::{DamageModifier, DamageType, Resistances};
+use crate::game::character::stats::Stats;
+use crate::game::item::inventory::{Inventory, InventoryError};
+use crate::game::item::{ItemAssets, ItemCategory, ItemDatabase, ItemId};
+use crate::screens::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        apply_equipment
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update),
+    );
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, Deserialize)]
+pub struct WeaponStats {
+    pub damage: usize,
+    pub damage_type: DamageType,
+    /// Multiplier on how fast swings are, so 2 halves their duration.
+    #[serde(default = "default_swing_speed")]
+    pub swing_speed: f32,
+    pub stamina_cost: usize,
+}
+
+fn default_swing_speed() -> f32 {
+    1.0
+}
+
+/// Slowest swing speed a weapon can have, so a speed of zero can't stretch a swing forever.
+const MIN_SWING_SPEED: f32 = 0.1;
+
+impl WeaponStats {
+    /// The swing speed, clamped to [`MIN_SWING_SPEED`].
+    pub fn swing_speed(&self) -> f32 {
+        self.swing_speed.max(MIN_SWING_SPEED)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Reflect, Deserialize)]
+pub struct ArmorStats {
+    #[serde(default)]
+    pub resistances: Vec<(DamageType, DamageModifier)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum EquipmentSlot {
+    Weapon,
+    Armor,
+    Trinket,
+}
+
+impl EquipmentSlot {
+    pub const ALL: [EquipmentSlot; 3] = [
+        EquipmentSlot::Weapon,
+        EquipmentSlot::Armor,
+        EquipmentSlot::Trinket,
+    ];
+
+    /// The slot items of the category are equipped in, if any.
+    pub fn for_category(category: ItemCategory) -> Option<Self> {
+        match category {
+            ItemCategory::Weapon => Some(EquipmentSlot::Weapon),
+            ItemCategory::Armor => Some(EquipmentSlot::Armor),
+            ItemCategory::Trinket => Some(EquipmentSlot::Trinket),
+            _ => None,
+        }
+    }
+
+    /// Source of the stat modifiers of the item in this slot.
+    fn modifier_source(&self) -> &'static str {
+        match self {
+            EquipmentSlot::Weapon => "equipment:weapon",
+            EquipmentSlot::Armor => "equipment:armor",
+            EquipmentSlot::Trinket => "equipment:trinket",
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct Equipment {
+    weapon: Option<ItemId>,
+    armor: Option<ItemId>,
+    trinket: Option<ItemId>,
+}
+
+impl Equipment {
+    pub fn with(mut self, slot: EquipmentSlot, item: impl Into<ItemId>) -> Self {
+        *self.slot_mut(slot) = Some(item.into());
+        self
+    }
+
+    pub fn get(&self, slot: EquipmentSlot) -> Option<&ItemId> {
+        match slot {
+            EquipmentSlot::Weapon => self.weapon.as_ref(),
+            EquipmentSlot::Armor => self.armor.as_ref(),
+            EquipmentSlot::Trinket => self.trinket.as_ref(),
+        }
+    }
+
+    /// Puts the item into the slot and returns the item it replaced.
+    pub fn equip(&mut self, slot: EquipmentSlot, item: ItemId) -> Option<ItemId> {
+        self.slot_mut(slot).replace(item)
+    }
+
+    /// Empties the slot and returns the item that was in it.
+    pub fn unequip(&mut self, slot: EquipmentSlot) -> Option<ItemId> {
+        self.slot_mut(slot).take()
+    }
+
+    fn slot_mut(&mut self, slot: EquipmentSlot) -> &mut Option<ItemId> {
+        match slot {
+            EquipmentSlot::Weapon => &mut self.weapon,
+            EquipmentSlot::Armor => &mut self.armor,
+            EquipmentSlot::Trinket => &mut self.trinket,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum EquipmentError {
+    #[error("Nothing equipped in the {0:?} slot")]
+    NothingEquipped(EquipmentSlot),
+    #[error(transparent)]
+    Inventory(#[from] InventoryError),
+}
+
+/// Equips the item in the inventory slot, putting whatever it replaces back into the
+/// inventory. Nothing changes if the replaced item doesn't fit.
+pub fn equip_from_inventory(
+    inventory: &mut Inventory,
+    equipment: &mut Equipment,
+    database: &ItemDatabase,
+    slot: usize,
+) -> Result<(), EquipmentError> {
+    let stack = inventory
+        .slots()
+        .get(slot)
+        .ok_or(InventoryError::InvalidSlot(slot))?
+        .as_ref()
+        .ok_or(InventoryError::EmptySlot(slot))?;
+
+    let definition = database
+        .get(&stack.item)
+        .ok_or_else(|| InventoryError::UnknownItem(stack.item.clone()))?;
+
+    let equipment_slot = EquipmentSlot::for_category(definition.category)
+        .ok_or_else(|| InventoryError::NotUsable(stack.item.clone()))?;
+
+    // The slot the new item comes from is only freed up if it was the last of its stack.
+    if let Some(previous) = equipment.get(equipment_slot)
+        && stack.count > 1
+        && inventory.space_for(database, previous)? == 0
+    {
+        return Err(InventoryError::Full(previous.clone()).into());
+    }
+
+    let item = inventory.take(slot, 1)?.item;
+
+    if let Some(previous) = equipment.equip(equipment_slot, item) {
+        inventory.add(database, &previous, 1)?;
+    }
+
+    Ok(())
+}
+
+/// Moves the item in the equipment slot back into the inventory, if it fits.
+pub fn unequip_to_inventory(
+    inventory: &mut Inventory,
+    equipment: &mut Equipment,
+    database: &ItemDatabase,
+    slot: EquipmentSlot,
+) -> Result<(), EquipmentError> {
+    let item = equipment
+        .get(slot)
+        .ok_or(EquipmentError::NothingEquipped(slot))?;
+
+    if inventory.space_for(database, item)? == 0 {
+        return Err(InventoryError::Full(item.clone()).into());
+    }
+
+    if let Some(item) = equipment.unequip(slot) {
+        inventory.add(database, &item, 1)?;
+    }
+
+    Ok(())
+}
+
+fn apply_equipment(
+    mut query: Query<
+        (
+            &Equipment,
+            Option<&mut Combo>,
+            Option<&mut Resistances>,
+            Option<&mut Stats>,
+        ),
+        Changed<Equipment>,
+    >,
+    item_assets: Res<ItemAssets>,
+    databases: Res<Assets<ItemDatabase>>,
+) {
+    let Some(database) = databases.get(&item_assets.database) else {
+        return;
+    };
+
+    for (equipment, combo, resistances, stats) in &mut query {
+        let equipped = |slot| equipment.get(slot).and_then(|item| database.get(item));
+
+        if let Some(mut combo) = combo {
+            let weapon = equipped(EquipmentSlot::Weapon).and_then(|item| item.weapon.clone());
+            combo.set_weapon(weapon);
+        }
+
+        if let Some(mut resistances) = resistances {
+            resistances.0 = EquipmentSlot::ALL
+                .into_iter()
+                .filter_map(equipped)
+                .filter_map(|item| item.armor.as_ref())
+                .flat_map(|armor| armor.resistances.iter().copied())
+                .collect();
+        }
+
+        if let Some(mut stats) = stats {
+            for slot in EquipmentSlot::ALL {
+                match equipped(slot) {
+                    Some(item) => stats.add_modifiers(
+                        slot.modifier_source(),
+                        item.modifiers.iter().copied(),
+                        None,
+                    ),
+                    None => stats.remove_source(slot.modifier_source()),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::item::inventory::ItemStack;
+    use crate::game::item::test_database;
+
+    fn database() -> ItemDatabase {
+        test_database(&[
+            ("sword", 1, ItemCategory::Weapon),
+            ("dagger", 2, ItemCategory::Weapon),
+            ("stone", 10, ItemCategory::Material),
+        ])
+    }
+
+    #[test]
+    fn equip_swaps_with_the_equipped_item() {
+        let database = database();
+        let mut inventory = Inventory::new(1).with_stack(ItemStack::new("dagger", 1));
+        let mut equipment = Equipment::default().with(EquipmentSlot::Weapon, "sword");
+
+        equip_from_inventory(&mut inventory, &mut equipment, &database, 0).unwrap();
+
+        assert_eq!(equipment.get(EquipmentSlot::Weapon), Some(&"dagger".into()));
+        assert_eq!(inventory.slots(), [Some(ItemStack::new("sword", 1))]);
+    }
+
+    #[test]
+    fn equip_keeps_everything_when_the_replaced_item_does_not_fit() {
+        let database = database();
+        let mut inventory = Inventory::new(1).with_stack(ItemStack::new("dagger", 2));
+        let mut equipment = Equipment::default().with(EquipmentSlot::Weapon, "sword");
+
+        assert_eq!(
+            equip_from_inventory(&mut inventory, &mut equipment, &database, 0),
+            Err(EquipmentError::Inventory(InventoryError::Full(
+                "sword".into()
+            )))
+        );
+
+        assert_eq!(equipment.get(EquipmentSlot::Weapon), Some(&"sword".into()));
+        assert_eq!(inventory.slots(), [Some(ItemStack::new("dagger", 2))]);
+    }
+
+    #[test]
+    fn unequip_moves_the_item_into_the_inventory() {
+        let database = database();
+        let mut inventory = Inventory::new(1);
+        let mut equipment = Equipment::default().with(EquipmentSlot::Weapon, "sword");
+
+        unequip_to_inventory(
+            &mut inventory,
+            &mut equipment,
+            &database,
+            EquipmentSlot::Weapon,
+        )
+        .unwrap();
+
+        assert_eq!(equipment.get(EquipmentSlot::Weapon), None);
+        assert_eq!(inventory.slots(), [Some(ItemStack::new("sword", 1))]);
+    }
+
+    #[test]
+    fn unequip_fails_without_room() {
+        let database = database();
+        let mut inventory = Inventory::new(1).with_stack(ItemStack::new("stone", 1));
+        let mut equipment = Equipment::default().with(EquipmentSlot::Weapon, "sword");
+
+        assert_eq!(
+            unequip_to_inventory(
+                &mut inventory,
+                &mut equipment,
+                &database,
+                EquipmentSlot::Weapon
+            ),
+            Err(EquipmentError::Inventory(InventoryError::Full(
+                "sword".into()
+            )))
+        );
+        assert_eq!(equipment.get(EquipmentSlot::Weapon), Some(&"sword".into()));
+    }
+
+    #[test]
+    fn unequip_fails_when_nothing_is_equipped() {
+        let database = database();
+        let mut inventory = Inventory::new(1);
+        let mut equipment = Equipment::default();
+
+        assert_eq!(
+            unequip_to_inventory(
+                &mut inventory,
+                &mut equipment,
+                &database,
+                EquipmentSlot::Weapon
+            ),
+            Err(EquipmentError::NothingEquipped(EquipmentSlot::Weapon))
+        );
+    }
+
+    #[test]
+    fn swing_speed_is_clamped() {
+        let weapon = WeaponStats {
+            damage: 10,
+            damage_type: DamageType::Slash,
+            swing_speed: 0.0,
+            stamina_cost: 10,
+        };
+
+        assert_eq!(weapon.swing_speed(), MIN_SWING_SPEED);
+    }
+}
@@ -0,0 +1,576 @@
+//! Inventories which hold stacks of items in a fixed number of slots.
+//!
+//! [`Inventory`] itself only does the bookkeeping, so it can be used without a world.
+//! Characters change their inventory by triggering an [`InventoryEvent`], which reports
+//! what happened with an [`InventoryChangedEvent`] or, for used items, an
+//! [`ItemUsedEvent`].
+
+use bevy::prelude::*;
+
+use crate::game::character::health::{HealthEvent, HealthEventType};
+use crate::game::character::stamina::{StaminaEvent, StaminaEventType};
+use crate::game::character::stats::AddModifiersEvent;
+use crate::game::item::equipment::{
+    Equipment, EquipmentError, EquipmentSlot, equip_from_inventory, unequip_to_inventory,
+};
+use crate::game::item::{ItemAssets, ItemCategory, ItemDatabase, ItemEffect, ItemId};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(on_inventory_event)
+        .add_observer(apply_item_effects);
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum InventoryError {
+    #[error("No item with id {0}")]
+    UnknownItem(ItemId),
+    #[error("No slot {0}")]
+    InvalidSlot(usize),
+    #[error("Slot {0} is empty")]
+    EmptySlot(usize),
+    #[error("Only {available} of {requested} {item} available")]
+    NotEnough {
+        item: ItemId,
+        requested: u32,
+        available: u32,
+    },
+    #[error("{0} can not be used")]
+    NotUsable(ItemId),
+    #[error("No room for {0}")]
+    Full(ItemId),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Reflect)]
+pub struct ItemStack {
+    pub item: ItemId,
+    pub count: u32,
+}
+
+impl ItemStack {
+    pub fn new(item: impl Into<ItemId>, count: u32) -> Self {
+        Self {
+            item: item.into(),
+            count,
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct Inventory {
+    slots: Vec<Option<ItemStack>>,
+}
+
+impl Inventory {
+    pub fn new(size: usize) -> Self {
+        Self {
+            slots: vec![None; size],
+        }
+    }
+
+    /// Puts the stack into the first empty slot, without checking stack sizes. Meant for
+    /// starting inventories, which are set up before item definitions are available.
+    pub fn with_stack(mut self, stack: ItemStack) -> Self {
+        if let Some(slot) = self.slots.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(stack);
+        }
+        self
+    }
+
+    pub fn slots(&self) -> &[Option<ItemStack>] {
+        &self.slots
+    }
+
+    /// How many of the item are in the inventory in total.
+    pub fn count(&self, item: &ItemId) -> u32 {
+        self.slots
+            .iter()
+            .flatten()
+            .filter(|stack| stack.item == *item)
+            .map(|stack| stack.count)
+            .sum()
+    }
+
+    /// The first slot holding the item.
+    pub fn find(&self, item: &ItemId) -> Option<usize> {
+        self.slots
+            .iter()
+            .position(|slot| slot.as_ref().is_some_and(|stack| stack.item == *item))
+    }
+
+    /// How many more of the item fit into the inventory.
+    pub fn space_for(&self, database: &ItemDatabase, item: &ItemId) -> Result<u32, InventoryError> {
+        let stack_size = database
+            .get(item)
+            .ok_or_else(|| InventoryError::UnknownItem(item.clone()))?
+            .stack_size
+            .max(1);
+
+        Ok(self
+            .slots
+            .iter()
+            .map(|slot| match slot {
+                Some(stack) if stack.item == *item => stack_size.saturating_sub(stack.count),
+                Some(_) => 0,
+                None => stack_size,
+            })
+            .sum())
+    }
+
+    /// Adds `count` of the item, topping up existing stacks before filling empty slots.
+    /// Returns how many did not fit.
+    pub fn add(
+        &mut self,
+        database: &ItemDatabase,
+        item: &ItemId,
+        count: u32,
+    ) -> Result<u32, InventoryError> {
+        let stack_size = database
+            .get(item)
+            .ok_or_else(|| InventoryError::UnknownItem(item.clone()))?
+            .stack_size
+            .max(1);
+
+        let mut remaining = count;
+
+        for stack in self.slots.iter_mut().flatten() {
+            if remaining == 0 {
+                break;
+            }
+            if stack.item == *item && stack.count < stack_size {
+                let added = remaining.min(stack_size - stack.count);
+                stack.count += added;
+                remaining -= added;
+            }
+        }
+
+        for slot in self.slots.iter_mut().filter(|slot| slot.is_none()) {
+            if remaining == 0 {
+                break;
+            }
+            let added = remaining.min(stack_size);
+            *slot = Some(ItemStack::new(item.clone(), added));
+            remaining -= added;
+        }
+
+        Ok(remaining)
+    }
+
+    /// Removes `count` of the item from wherever it is, starting with the last stack.
+    /// Nothing is removed if there are not enough.
+    pub fn remove(&mut self, item: &ItemId, count: u32) -> Result<(), InventoryError> {
+        let available = self.count(item);
+        if available < count {
+            return Err(InventoryError::NotEnough {
+                item: item.clone(),
+                requested: count,
+                available,
+            });
+        }
+
+        let mut remaining = count;
+
+        for slot in self.slots.iter_mut().rev() {
+            if remaining == 0 {
+                break;
+            }
+            if let Some(stack) = slot
+                && stack.item == *item
+            {
+                let removed = remaining.min(stack.count);
+                stack.count -= removed;
+                remaining -= removed;
+
+                if stack.count == 0 {
+                    *slot = None;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes up to `count` items from the slot and returns them.
+    pub fn take(&mut self, slot: usize, count: u32) -> Result<ItemStack, InventoryError> {
+        let stack = self
+            .slots
+            .get_mut(slot)
+            .ok_or(InventoryError::InvalidSlot(slot))?
+            .as_mut()
+            .ok_or(InventoryError::EmptySlot(slot))?;
+
+        let taken = ItemStack::new(stack.item.clone(), count.min(stack.count));
+        stack.count -= taken.count;
+
+        if stack.count == 0 {
+            self.slots[slot] = None;
+        }
+
+        Ok(taken)
+    }
+
+    /// Moves the stack in `from` to `to`. Stacks of the same item are merged as far as
+    /// they fit, anything else is swapped, as are stacks of the same item when `to` is
+    /// already full.
+    pub fn move_stack(
+        &mut self,
+        database: &ItemDatabase,
+        from: usize,
+        to: usize,
+    ) -> Result<(), InventoryError> {
+        if from >= self.slots.len() {
+            return Err(InventoryError::InvalidSlot(from));
+        }
+        if to >= self.slots.len() {
+            return Err(InventoryError::InvalidSlot(to));
+        }
+        if self.slots[from].is_none() {
+            return Err(InventoryError::EmptySlot(from));
+        }
+        if from == to {
+            return Ok(());
+        }
+
+        if let (Some(source), Some(target)) = (&self.slots[from], &self.slots[to])
+            && source.item == target.item
+        {
+            let stack_size = database
+                .get(&source.item)
+                .ok_or_else(|| InventoryError::UnknownItem(source.item.clone()))?
+                .stack_size
+                .max(1);
+            let moved = source.count.min(stack_size.saturating_sub(target.count));
+
+            if moved == 0 {
+                self.slots.swap(from, to);
+                return Ok(());
+            }
+
+            if let Some(target) = &mut self.slots[to] {
+                target.count += moved;
+            }
+            self.take(from, moved)?;
+        } else {
+            self.slots.swap(from, to);
+        }
+
+        Ok(())
+    }
+
+    /// Removes one consumable from the slot and returns its id, so its effects can be
+    /// applied.
+    pub fn use_item(
+        &mut self,
+        database: &ItemDatabase,
+        slot: usize,
+    ) -> Result<ItemId, InventoryError> {
+        let stack = self
+            .slots
+            .get(slot)
+            .ok_or(InventoryError::InvalidSlot(slot))?
+            .as_ref()
+            .ok_or(InventoryError::EmptySlot(slot))?;
+
+        let definition = database
+            .get(&stack.item)
+            .ok_or_else(|| InventoryError::UnknownItem(stack.item.clone()))?;
+
+        if definition.category != ItemCategory::Consumable {
+            return Err(InventoryError::NotUsable(stack.item.clone()));
+        }
+
+        Ok(self.take(slot, 1)?.item)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Reflect)]
+pub enum InventoryAction {
+    Add(ItemId, u32),
+    Remove(ItemId, u32),
+    Move { from: usize, to: usize },
+    Use(usize),
+    Unequip(EquipmentSlot),
+}
+
+/// Changes an entity's [`Inventory`].
+#[derive(EntityEvent, Debug, Clone)]
+pub struct InventoryEvent {
+    entity: Entity,
+    action: InventoryAction,
+}
+
+impl InventoryEvent {
+    pub fn new(entity: Entity, action: InventoryAction) -> Self {
+        Self { entity, action }
+    }
+}
+
+/// Triggered on an entity after an [`InventoryEvent`] changed its inventory.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct InventoryChangedEvent {
+    entity: Entity,
+    pub action: InventoryAction,
+    /// How many items did not fit when adding.
+    pub overflow: u32,
+}
+
+/// Triggered on an entity after it used an item, which then applies the item's effects.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct ItemUsedEvent {
+    entity: Entity,
+    pub item: ItemId,
+}
+
+fn on_inventory_event(
+    event: On<InventoryEvent>,
+    item_assets: Res<ItemAssets>,
+    databases: Res<Assets<ItemDatabase>>,
+    mut query: Query<(&mut Inventory, Option<&mut Equipment>)>,
+    mut commands: Commands,
+) {
+    let Ok((mut inventory, equipment)) = query.get_mut(event.entity) else {
+        return;
+    };
+
+    let Some(database) = databases.get(&item_assets.database) else {
+        return;
+    };
+
+    let result: Result<u32, EquipmentError> = match &event.action {
+        InventoryAction::Add(item, count) => {
+            inventory.add(database, item, *count).map_err(Into::into)
+        }
+        InventoryAction::Remove(item, count) => inventory
+            .remove(item, *count)
+            .map(|_| 0)
+            .map_err(Into::into),
+        InventoryAction::Move { from, to } => inventory
+            .move_stack(database, *from, *to)
+            .map(|_| 0)
+            .map_err(Into::into),
+        InventoryAction::Use(slot) => {
+            match (equipment_in(&inventory, database, *slot), equipment) {
+                (Some(_), Some(mut equipment)) => {
+                    equip_from_inventory(&mut inventory, &mut equipment, database, *slot).map(|_| 0)
+                }
+                (Some(item), None) => Err(InventoryError::NotUsable(item).into()),
+                (None, _) => inventory
+                    .use_item(database, *slot)
+                    .map(|item| {
+                        commands.trigger(ItemUsedEvent {
+                            entity: event.entity,
+                            item,
+                        });
+                        0
+                    })
+                    .map_err(Into::into),
+            }
+        }
+        InventoryAction::Unequip(slot) => match equipment {
+            Some(mut equipment) => {
+                unequip_to_inventory(&mut inventory, &mut equipment, database, *slot).map(|_| 0)
+            }
+            None => Err(EquipmentError::NothingEquipped(*slot)),
+        },
+    };
+
+    match result {
+        Ok(overflow) => commands.trigger(InventoryChangedEvent {
+            entity: event.entity,
+            action: event.action.clone(),
+            overflow,
+        }),
+        Err(error) => warn!("Inventory action {:?} failed: {error}", event.action),
+    }
+}
+
+/// The item in the slot, if it goes into an [`EquipmentSlot`] rather than being used up.
+fn equipment_in(inventory: &Inventory, database: &ItemDatabase, slot: usize) -> Option<ItemId> {
+    let stack = inventory.slots().get(slot)?.as_ref()?;
+    let definition = database.get(&stack.item)?;
+    EquipmentSlot::for_category(definition.category).map(|_| stack.item.clone())
+}
+
+fn apply_item_effects(
+    event: On<ItemUsedEvent>,
+    item_assets: Res<ItemAssets>,
+    databases: Res<Assets<ItemDatabase>>,
+    mut commands: Commands,
+) {
+    let entity = event.entity;
+    let item = &event.item;
+
+    let Some(definition) = databases
+        .get(&item_assets.database)
+        .and_then(|database| database.get(item))
+    else {
+        return;
+    };
+
+    for effect in &definition.effects {
+        match effect {
+            ItemEffect::Heal(amount) => {
+                commands.trigger(HealthEvent::new(entity, HealthEventType::Heal(*amount)));
+            }
+            ItemEffect::RestoreStamina(amount) => {
+                commands.trigger(StaminaEvent::new(
+                    entity,
+                    StaminaEventType::Restore(*amount),
+                ));
+            }
+            ItemEffect::Buff {
+                modifiers,
+                duration,
+            } => {
+                commands.trigger(AddModifiersEvent::new(
+                    entity,
+                    format!("item:{item}"),
+                    modifiers.clone(),
+                    Some(*duration),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::item::test_database;
+
+    fn database() -> ItemDatabase {
+        test_database(&[
+            ("potion", 5, ItemCategory::Consumable),
+            ("stone", 10, ItemCategory::Material),
+        ])
+    }
+
+    #[test]
+    fn add_tops_up_stacks_before_empty_slots() {
+        let database = database();
+        let mut inventory = Inventory::new(3).with_stack(ItemStack::new("potion", 3));
+
+        assert_eq!(inventory.add(&database, &"potion".into(), 4), Ok(0));
+
+        assert_eq!(
+            inventory.slots(),
+            [
+                Some(ItemStack::new("potion", 5)),
+                Some(ItemStack::new("potion", 2)),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn add_returns_overflow() {
+        let database = database();
+        let mut inventory = Inventory::new(2).with_stack(ItemStack::new("stone", 1));
+
+        assert_eq!(inventory.space_for(&database, &"potion".into()), Ok(5));
+        assert_eq!(inventory.add(&database, &"potion".into(), 7), Ok(2));
+        assert_eq!(inventory.count(&"potion".into()), 5);
+        assert_eq!(
+            inventory.add(&database, &"sword".into(), 1),
+            Err(InventoryError::UnknownItem("sword".into()))
+        );
+    }
+
+    #[test]
+    fn remove_takes_from_the_last_stack() {
+        let mut inventory = Inventory::new(2)
+            .with_stack(ItemStack::new("potion", 5))
+            .with_stack(ItemStack::new("potion", 2));
+
+        assert_eq!(inventory.remove(&"potion".into(), 3), Ok(()));
+
+        assert_eq!(inventory.slots(), [Some(ItemStack::new("potion", 4)), None]);
+    }
+
+    #[test]
+    fn remove_needs_enough_items() {
+        let mut inventory = Inventory::new(1).with_stack(ItemStack::new("potion", 2));
+
+        assert_eq!(
+            inventory.remove(&"potion".into(), 3),
+            Err(InventoryError::NotEnough {
+                item: "potion".into(),
+                requested: 3,
+                available: 2,
+            })
+        );
+        assert_eq!(inventory.count(&"potion".into()), 2);
+    }
+
+    #[test]
+    fn take_splits_a_stack() {
+        let mut inventory = Inventory::new(1).with_stack(ItemStack::new("stone", 8));
+
+        assert_eq!(inventory.take(0, 3), Ok(ItemStack::new("stone", 3)));
+        assert_eq!(inventory.slots(), [Some(ItemStack::new("stone", 5))]);
+
+        assert_eq!(inventory.take(0, 9), Ok(ItemStack::new("stone", 5)));
+        assert_eq!(inventory.slots(), [None]);
+        assert_eq!(inventory.take(0, 1), Err(InventoryError::EmptySlot(0)));
+    }
+
+    #[test]
+    fn move_merges_stacks_of_the_same_item() {
+        let database = database();
+        let mut inventory = Inventory::new(2)
+            .with_stack(ItemStack::new("potion", 4))
+            .with_stack(ItemStack::new("potion", 3));
+
+        assert_eq!(inventory.move_stack(&database, 0, 1), Ok(()));
+
+        assert_eq!(
+            inventory.slots(),
+            [
+                Some(ItemStack::new("potion", 2)),
+                Some(ItemStack::new("potion", 5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn move_swaps_different_items_and_full_stacks() {
+        let database = database();
+        let mut inventory = Inventory::new(3)
+            .with_stack(ItemStack::new("potion", 2))
+            .with_stack(ItemStack::new("stone", 1))
+            .with_stack(ItemStack::new("potion", 5));
+
+        assert_eq!(inventory.move_stack(&database, 0, 1), Ok(()));
+        assert_eq!(
+            inventory.slots()[..2],
+            [
+                Some(ItemStack::new("stone", 1)),
+                Some(ItemStack::new("potion", 2)),
+            ]
+        );
+
+        assert_eq!(inventory.move_stack(&database, 1, 2), Ok(()));
+        assert_eq!(
+            inventory.slots()[1..],
+            [
+                Some(ItemStack::new("potion", 5)),
+                Some(ItemStack::new("potion", 2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn move_checks_slots() {
+        let database = database();
+        let mut inventory = Inventory::new(2).with_stack(ItemStack::new("potion", 1));
+
+        assert_eq!(
+            inventory.move_stack(&database, 1, 0),
+            Err(InventoryError::EmptySlot(1))
+        );
+        assert_eq!(
+            inventory.move_stack(&database, 0, 2),
+            Err(InventoryError::InvalidSlot(2))
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Items and the definitions they are created from.
+//!
+//! Item definitions are loaded from `.items.ron` asset files into an [`ItemDatabase`].
+//! Everything else refers to items by their [`ItemId`].
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::asset_tracking::{LoadResource, RegisterRonAsset};
+use crate::game::character::stats::StatModifier;
+use crate::game::item::equipment::{ArmorStats, WeaponStats};
+
+pub mod equipment;
+pub mod inventory;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ron_asset::<ItemDatabase>(&["items.ron"]);
+    app.load_resource::<ItemAssets>();
+
+    app.add_plugins((equipment::plugin, inventory::plugin));
+}
+
+/// Identifies an [`ItemDefinition`], e.g. `"health_potion"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Reflect, Deserialize)]
+#[serde(transparent)]
+pub struct ItemId(pub String);
+
+impl From<&str> for ItemId {
+    fn from(id: &str) -> Self {
+        Self(id.to_string())
+    }
+}
+
+impl std::fmt::Display for ItemId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug, Clone, Reflect, Deserialize)]
+pub struct ItemDefinition {
+    pub id: ItemId,
+    pub name: String,
+    /// Asset path of the item's icon.
+    pub icon: String,
+    /// How many of the item fit into one inventory slot.
+    #[serde(default = "default_stack_size")]
+    pub stack_size: u32,
+    pub category: ItemCategory,
+    /// What happens when the item is used. Consumables are used up, equipment is
+    /// equipped.
+    #[serde(default)]
+    pub effects: Vec<ItemEffect>,
+    #[serde(default)]
+    pub weapon: Option<WeaponStats>,
+    #[serde(default)]
+    pub armor: Option<ArmorStats>,
+    /// Stat modifiers applied while the item is equipped.
+    #[serde(default)]
+    pub modifiers: Vec<StatModifier>,
+}
+
+fn default_stack_size() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Deserialize)]
+pub enum ItemCategory {
+    Consumable,
+    Weapon,
+    Armor,
+    Trinket,
+    Material,
+    Quest,
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, Deserialize)]
+pub enum ItemEffect {
+    Heal(usize),
+    RestoreStamina(usize),
+    /// Applies stat modifiers for `duration` seconds.
+    Buff {
+        modifiers: Vec<StatModifier>,
+        duration: f32,
+    },
+}
+
+/// All item definitions, as written in an asset file.
+#[derive(Asset, TypePath, Debug, Clone, Default, Deserialize)]
+pub struct ItemDatabase {
+    pub items: Vec<ItemDefinition>,
+}
+
+impl ItemDatabase {
+    pub fn get(&self, id: &ItemId) -> Option<&ItemDefinition> {
+        self.items.iter().find(|item| item.id == *id)
+    }
+}
+
+/// A database of plain items without effects or stats, for tests. Each item is given as
+/// its id, stack size and category.
+#[cfg(test)]
+pub(crate) fn test_database(items: &[(&str, u32, ItemCategory)]) -> ItemDatabase {
+    ItemDatabase {
+        items: items
+            .iter()
+            .map(|&(id, stack_size, category)| ItemDefinition {
+                id: id.into(),
+                name: id.to_string(),
+                icon: String::new(),
+                stack_size,
+                category,
+                effects: Vec::new(),
+                weapon: None,
+                armor: None,
+                modifiers: Vec::new(),
+            })
+            .collect(),
+    }
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct ItemAssets {
+    #[dependency]
+    pub database: Handle<ItemDatabase>,
+}
+
+impl FromWorld for ItemAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            database: assets.load("items/base.items.ron"),
+        }
+    }
+}
@@ -1,17 +1,24 @@
 //! Spawn the main level.
 
 use bevy::prelude::*;
-use std::collections::BTreeMap;
 use std::error::Error;
 use std::str::FromStr;
-use std::sync::{Arc, RwLock};
 
 use crate::game::character::CharacterAssets;
+use crate::game::character::combo::ComboDefinition;
+use crate::game::character::enemy::{EnemyAssets, enemy, ranged_enemy};
 use crate::game::character::player::{PlayerAssets, player};
+use crate::game::chest::{ChestAssets, chest};
 use crate::game::grid::coords::TileCoords;
 use crate::game::grid::tile::{TileEdges, TileFacing, TileMaterial, TileType, tile};
-use crate::game::grid::{TileAssets, grid};
+use crate::game::grid::{TileAssets, TileMap, grid};
+use crate::game::item::inventory::ItemStack;
+use crate::game::npc::{NpcAssets, ducky};
 use crate::game::object::{ObjectAssets, ObjectType, object};
+use crate::game::party::Party;
+use crate::game::pickup::{PickupKind, SpawnPickupEvent};
+use crate::game::projectile::ProjectileAssets;
+use crate::game::quest::quest_area;
 use crate::{Scale, asset_tracking::LoadResource, audio::music, screens::Screen};
 
 pub(super) fn plugin(app: &mut App) {
@@ -34,14 +41,24 @@ impl FromWorld for LevelAssets {
     }
 }
 
+/// How far apart the players spawn in local co-op.
+const PLAYER_SPACING: f32 = 0.75;
+
 /// A system that spawns the main level.
 pub fn spawn_level(
     mut commands: Commands,
     scale: Res<Scale>,
     level_assets: Res<LevelAssets>,
     player_assets: Res<PlayerAssets>,
+    combos: Res<Assets<ComboDefinition>>,
+    asset_server: Res<AssetServer>,
+    enemy_assets: Res<EnemyAssets>,
+    projectile_assets: Res<ProjectileAssets>,
     tile_assets: Res<TileAssets>,
     object_assets: Res<ObjectAssets>,
+    chest_assets: Res<ChestAssets>,
+    npc_assets: Res<NpcAssets>,
+    party: Res<Party>,
     _character_assets: Res<CharacterAssets>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
 ) {
@@ -52,11 +69,24 @@ pub fn spawn_level(
             Visibility::default(),
             DespawnOnExit(Screen::Gameplay),
             children![
-                player(
-                    Vec3::new(7.0, 1.0, 8.0),
-                    //Vec3::new(0.0, 1.0, 0.0),
-                    3.5,
-                    &player_assets,
+                enemy(
+                    Vec3::new(3.0, 1.0, 3.0),
+                    vec![
+                        Vec3::new(2.0, 1.0, 2.0),
+                        Vec3::new(2.0, 1.0, 6.0),
+                        Vec3::new(6.0, 1.0, 2.0),
+                    ],
+                    2.0,
+                    &enemy_assets,
+                    &mut texture_atlas_layouts,
+                    scale.0
+                ),
+                ranged_enemy(
+                    Vec3::new(8.0, 1.0, 2.0),
+                    vec![Vec3::new(8.0, 1.0, 2.0), Vec3::new(4.0, 1.0, 2.0)],
+                    1.5,
+                    &enemy_assets,
+                    &projectile_assets,
                     &mut texture_atlas_layouts,
                     scale.0
                 ),
@@ -72,10 +102,37 @@ pub fn spawn_level(
                     0.5,
                     0.5,
                 ),
+                chest("chest", &chest_assets, Vec3::new(2.0, 1.0, 9.0), scale.0),
+                ducky(
+                    &npc_assets,
+                    &mut texture_atlas_layouts,
+                    Vec3::new(9.0, 1.0, 6.0),
+                    scale.0
+                ),
+                quest_area("chest_corner", Vec3::new(2.0, 1.0, 9.0), 1.5),
             ],
         ))
         .id();
 
+    // One player per party member, lined up next to each other.
+    for (index, source) in party.sources().into_iter().enumerate() {
+        let player = commands
+            .spawn(player(
+                index,
+                source,
+                Vec3::new(7.0, 1.0, 8.0 - PLAYER_SPACING * index as f32),
+                //Vec3::new(0.0, 1.0, 0.0),
+                3.5,
+                &player_assets,
+                &combos,
+                &asset_server,
+                &mut texture_atlas_layouts,
+                scale.0,
+            ))
+            .id();
+        commands.entity(level).add_child(player);
+    }
+
     let grid = create_level(
         commands.reborrow(),
         scale,
@@ -84,6 +141,17 @@ pub fn spawn_level(
     );
 
     commands.entity(level).add_child(grid);
+
+    for (kind, position) in [
+        (PickupKind::Heal(50), Vec3::new(5.0, 1.0, 8.0)),
+        (PickupKind::RestoreStamina(100), Vec3::new(9.0, 1.0, 8.0)),
+        (
+            PickupKind::Item(ItemStack::new("stamina_potion", 2)),
+            Vec3::new(7.0, 1.0, 5.0),
+        ),
+    ] {
+        commands.trigger(SpawnPickupEvent { kind, position });
+    }
 }
 
 fn create_level(
@@ -92,7 +160,7 @@ fn create_level(
     tile_assets: Res<TileAssets>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
 ) -> Entity {
-    let tile_map = Arc::new(RwLock::new(BTreeMap::<TileCoords, Entity>::new()));
+    let mut tile_map = TileMap::default();
 
     let level_1 = [
         "L=xz:G__,L=z:G___,L=z:G___,L=z:G___,L=z:G___,L=z:G___,L=z:G___,L=z:G___,L=z:G___,L=z:G___,L=Xz:G__,________,________,________,________,________,________,________,________,________,________,________,",
@@ -250,13 +318,13 @@ fn create_level(
         }
     }
 
-    let grid = grid(tile_map.clone(), scale.0);
+    let grid = grid(scale.0);
     let grid = commands.spawn(grid).id();
 
     for (material, tile_type, coords) in tile_coords {
         let tile = commands
             .spawn(tile(
-                tile_type,
+                tile_type.clone(),
                 material,
                 coords.clone(),
                 &tile_assets,
@@ -266,9 +334,11 @@ fn create_level(
 
         commands.entity(grid).add_child(tile);
 
-        tile_map.write().unwrap().insert(coords, tile);
+        tile_map.insert(coords, tile_type);
     }
 
+    commands.insert_resource(tile_map);
+
     grid
 }
 
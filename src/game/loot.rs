@@ -0,0 +1,346 @@
+//! Weighted loot tables, rolled when enemies die or chests open.
+//!
+//! Tables are loaded from `.loot.ron` asset files into a [`LootDatabase`]. An entity with
+//! [`Loot`] drops a roll of its table as pickups around it whenever a [`DropLootEvent`]
+//! is triggered on it.
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+
+use crate::asset_tracking::{LoadResource, RegisterRonAsset};
+use crate::game::grid::coords::WorldPosition;
+use crate::game::item::ItemId;
+use crate::game::item::inventory::ItemStack;
+use crate::game::pickup::{PickupKind, SpawnPickupEvent};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ron_asset::<LootDatabase>(&["loot.ron"]);
+    app.load_resource::<LootAssets>();
+    app.init_resource::<LootRng>();
+
+    app.add_observer(on_drop_loot);
+}
+
+/// How deep tables may refer to other tables, which guards against cycles.
+const MAX_TABLE_DEPTH: usize = 8;
+
+/// Drops are scattered between these distances around the source, so they don't all
+/// land on top of each other or inside the source's collider.
+const SCATTER_MIN: f32 = 0.5;
+const SCATTER_MAX: f32 = 0.9;
+
+/// An inclusive range of how many of something to roll, e.g. `(1, 3)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Deserialize)]
+pub struct Quantity(pub u32, pub u32);
+
+impl Default for Quantity {
+    fn default() -> Self {
+        Self(1, 1)
+    }
+}
+
+impl Quantity {
+    fn roll(&self, rng: &mut impl Rng) -> u32 {
+        let (min, max) = (self.0.min(self.1), self.0.max(self.1));
+        rng.random_range(min..=max)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, Deserialize)]
+pub enum LootDrop {
+    /// Lets weighted entries roll nothing at all.
+    Nothing,
+    Item {
+        id: ItemId,
+        #[serde(default)]
+        count: Quantity,
+    },
+    Heal(usize),
+    RestoreStamina(usize),
+    /// Rolls another table in the same database.
+    Table(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, Deserialize)]
+pub struct LootEntry {
+    pub weight: u32,
+    pub drop: LootDrop,
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, Deserialize)]
+pub struct LootTable {
+    pub id: String,
+    /// Dropped every time, in addition to the rolls.
+    #[serde(default)]
+    pub guaranteed: Vec<LootDrop>,
+    /// How many times to pick one of the weighted entries.
+    #[serde(default)]
+    pub rolls: Quantity,
+    #[serde(default)]
+    pub entries: Vec<LootEntry>,
+}
+
+/// All loot tables, as written in an asset file.
+#[derive(Asset, TypePath, Debug, Clone, Default, Deserialize)]
+pub struct LootDatabase {
+    pub tables: Vec<LootTable>,
+}
+
+impl LootDatabase {
+    pub fn get(&self, id: &str) -> Option<&LootTable> {
+        self.tables.iter().find(|table| table.id == id)
+    }
+
+    /// Rolls the table and returns what it dropped.
+    pub fn roll(&self, id: &str, rng: &mut impl Rng) -> Vec<PickupKind> {
+        let mut drops = Vec::new();
+        self.roll_into(id, rng, 0, &mut drops);
+        drops
+    }
+
+    fn roll_into(&self, id: &str, rng: &mut impl Rng, depth: usize, drops: &mut Vec<PickupKind>) {
+        if depth > MAX_TABLE_DEPTH {
+            warn!("Loot table {id} is nested too deeply");
+            return;
+        }
+
+        let Some(table) = self.get(id) else {
+            warn!("No loot table with id {id}");
+            return;
+        };
+
+        for drop in &table.guaranteed {
+            self.resolve(drop, rng, depth, drops);
+        }
+
+        for _ in 0..table.rolls.roll(rng) {
+            if let Ok(entry) = table.entries.choose_weighted(rng, |entry| entry.weight) {
+                self.resolve(&entry.drop, rng, depth, drops);
+            }
+        }
+    }
+
+    fn resolve(
+        &self,
+        drop: &LootDrop,
+        rng: &mut impl Rng,
+        depth: usize,
+        drops: &mut Vec<PickupKind>,
+    ) {
+        match drop {
+            LootDrop::Nothing => {}
+            LootDrop::Item { id, count } => {
+                let count = count.roll(rng);
+                if count > 0 {
+                    drops.push(PickupKind::Item(ItemStack::new(id.clone(), count)));
+                }
+            }
+            LootDrop::Heal(amount) => drops.push(PickupKind::Heal(*amount)),
+            LootDrop::RestoreStamina(amount) => drops.push(PickupKind::RestoreStamina(*amount)),
+            LootDrop::Table(id) => self.roll_into(id, rng, depth + 1, drops),
+        }
+    }
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct LootAssets {
+    #[dependency]
+    pub database: Handle<LootDatabase>,
+}
+
+impl FromWorld for LootAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            database: assets.load("loot/base.loot.ron"),
+        }
+    }
+}
+
+/// The random number generator all loot is rolled with. Set the `LOOT_SEED` environment
+/// variable to get the same drops every run.
+#[derive(Resource, Debug)]
+pub struct LootRng(StdRng);
+
+impl Default for LootRng {
+    fn default() -> Self {
+        match std::env::var("LOOT_SEED")
+            .ok()
+            .and_then(|seed| seed.parse().ok())
+        {
+            Some(seed) => Self::seeded(seed),
+            None => Self(StdRng::from_os_rng()),
+        }
+    }
+}
+
+impl LootRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
+/// The loot table an entity drops from.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Loot(pub String);
+
+impl Loot {
+    pub fn new(table: impl Into<String>) -> Self {
+        Self(table.into())
+    }
+}
+
+/// Rolls an entity's [`Loot`] and spawns the drops around its [`WorldPosition`].
+#[derive(EntityEvent, Debug, Clone)]
+pub struct DropLootEvent {
+    entity: Entity,
+}
+
+impl DropLootEvent {
+    pub fn new(entity: Entity) -> Self {
+        Self { entity }
+    }
+}
+
+fn on_drop_loot(
+    event: On<DropLootEvent>,
+    query: Query<(&Loot, &WorldPosition)>,
+    loot_assets: Res<LootAssets>,
+    databases: Res<Assets<LootDatabase>>,
+    mut rng: ResMut<LootRng>,
+    mut commands: Commands,
+) {
+    let Ok((Loot(table), position)) = query.get(event.entity) else {
+        return;
+    };
+
+    let Some(database) = databases.get(&loot_assets.database) else {
+        return;
+    };
+
+    let rng = &mut rng.0;
+
+    for kind in database.roll(table, rng) {
+        let angle = rng.random_range(0.0..std::f32::consts::TAU);
+        let distance = rng.random_range(SCATTER_MIN..SCATTER_MAX);
+        let offset = Vec3::new(angle.cos(), 0.0, angle.sin()) * distance;
+
+        commands.trigger(SpawnPickupEvent {
+            kind,
+            position: position.as_vec3() + offset,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn database(ron: &str) -> LootDatabase {
+        ron::from_str(ron).unwrap()
+    }
+
+    fn rng(seed: u64) -> StdRng {
+        LootRng::seeded(seed).0
+    }
+
+    #[test]
+    fn guaranteed_drops_always_drop() {
+        let database = database(
+            r#"(tables: [(
+                id: "chest",
+                guaranteed: [Heal(5)],
+                rolls: (0, 0),
+                entries: [(weight: 1, drop: RestoreStamina(5))],
+            )])"#,
+        );
+
+        for seed in 0..20 {
+            assert_eq!(
+                database.roll("chest", &mut rng(seed)),
+                [PickupKind::Heal(5)]
+            );
+        }
+    }
+
+    #[test]
+    fn same_seed_rolls_the_same_drops() {
+        let database = database(
+            r#"(tables: [(
+                id: "enemy",
+                rolls: (1, 3),
+                entries: [
+                    (weight: 1, drop: Nothing),
+                    (weight: 2, drop: Heal(5)),
+                    (weight: 2, drop: Item(id: "stone", count: (1, 5))),
+                ],
+            )])"#,
+        );
+
+        for seed in 0..20 {
+            assert_eq!(
+                database.roll("enemy", &mut rng(seed)),
+                database.roll("enemy", &mut rng(seed))
+            );
+        }
+    }
+
+    #[test]
+    fn quantities_stay_in_range() {
+        // The bounds may be given either way round.
+        let database = database(
+            r#"(tables: [(
+                id: "stones",
+                guaranteed: [Item(id: "stone", count: (4, 2))],
+            )])"#,
+        );
+
+        let mut rng = rng(1);
+        let counts = (0..100)
+            .map(|_| match database.roll("stones", &mut rng).as_slice() {
+                [PickupKind::Item(stack)] => stack.count,
+                drops => panic!("Expected a single stack, got {drops:?}"),
+            })
+            .collect::<Vec<_>>();
+
+        assert!(counts.iter().all(|count| (2..=4).contains(count)));
+        assert!((2..=4).all(|count| counts.contains(&count)));
+    }
+
+    #[test]
+    fn nested_tables_are_rolled() {
+        let database = database(
+            r#"(tables: [
+                (id: "boss", guaranteed: [Table("gems"), Heal(10)]),
+                (id: "gems", guaranteed: [Item(id: "ruby")]),
+            ])"#,
+        );
+
+        assert_eq!(
+            database.roll("boss", &mut rng(0)),
+            [
+                PickupKind::Item(ItemStack::new("ruby", 1)),
+                PickupKind::Heal(10),
+            ]
+        );
+    }
+
+    #[test]
+    fn nesting_stops_at_max_depth() {
+        let database = database(
+            r#"(tables: [
+                (id: "cycle", guaranteed: [Heal(1), Table("cycle")]),
+            ])"#,
+        );
+
+        assert_eq!(
+            database.roll("cycle", &mut rng(0)).len(),
+            MAX_TABLE_DEPTH + 1
+        );
+    }
+}
@@ -1,19 +1,47 @@
 use bevy::prelude::*;
 
+pub mod ai;
 pub mod character;
+mod chest;
+pub mod dialogue;
+pub mod flags;
 mod grid;
+mod interaction;
+pub mod item;
 pub mod level;
+mod loot;
+mod npc;
 mod object;
 mod particle;
+pub mod party;
 mod physics;
+mod pickup;
+mod projectile;
+pub mod quest;
+mod save;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
+        ai::plugin,
         character::plugin,
+        chest::plugin,
+        dialogue::plugin,
+        flags::plugin,
         grid::plugin,
+        interaction::plugin,
+        item::plugin,
         level::plugin,
+        loot::plugin,
+    ));
+    app.add_plugins((
+        npc::plugin,
         particle::plugin,
+        party::plugin,
+        pickup::plugin,
+        projectile::plugin,
         physics::plugin,
         object::plugin,
+        quest::plugin,
+        save::plugin,
     ));
 }
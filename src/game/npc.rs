@@ -0,0 +1,82 @@
+//! Non-player characters, which can be talked to.
+
+use bevy::prelude::*;
+
+use crate::asset_tracking::LoadResource;
+use crate::game::dialogue::{Dialogue, DialogueTree};
+use crate::game::grid::coords::WorldPosition;
+use crate::game::interaction::Interactable;
+use crate::game::object::Shadow;
+use crate::game::physics::components::{Collider, PhysicsData};
+use crate::game::quest::QuestTarget;
+
+pub(super) fn plugin(app: &mut App) {
+    app.load_resource::<NpcAssets>();
+}
+
+/// How close a player has to get to an NPC to talk to it.
+const TALK_RADIUS: f32 = 1.2;
+
+#[derive(Component, Debug, Clone, Copy, Default, Reflect)]
+#[reflect(Component)]
+pub struct Npc;
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct NpcAssets {
+    #[dependency]
+    ducky: Handle<Image>,
+    #[dependency]
+    ducky_shadow: Handle<Image>,
+    #[dependency]
+    ducky_dialogue: Handle<DialogueTree>,
+}
+
+impl FromWorld for NpcAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            ducky: assets.load("images/ducky2.png"),
+            ducky_shadow: assets.load("images/ducky_shadow.png"),
+            ducky_dialogue: assets.load("dialogue/ducky.dialogue.ron"),
+        }
+    }
+}
+
+/// A duck standing around, happy to chat.
+pub fn ducky(
+    assets: &NpcAssets,
+    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+    position: Vec3,
+    scale: f32,
+) -> impl Bundle {
+    let layout = TextureAtlasLayout::from_grid(UVec2::splat(16), 6, 2, None, None);
+    let layout = texture_atlas_layouts.add(layout);
+    let shadow = assets.ducky_shadow.clone();
+
+    (
+        Name::new("Ducky"),
+        Npc,
+        Dialogue(assets.ducky_dialogue.clone()),
+        Interactable::new("Talk", TALK_RADIUS),
+        QuestTarget::new("ducky"),
+        WorldPosition(position.into()),
+        Transform::from_scale(Vec3::splat(scale)),
+        // Physics
+        Collider::vertical_capsule(0.5, 0.25, position),
+        PhysicsData::Static,
+        // Rendering
+        Sprite::from_atlas_image(assets.ducky.clone(), TextureAtlas { layout, index: 0 }),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Shadow,
+                Sprite {
+                    image: shadow,
+                    color: Color::srgba(1.0, 1.0, 1.0, 0.75),
+                    ..default()
+                },
+                Transform::from_translation(Vec3::new(0.0, -6.0, -0.1)),
+            ));
+        })),
+    )
+}
@@ -0,0 +1,56 @@
+//! The local players and the device each of them plays with.
+
+use bevy::prelude::*;
+
+use crate::input::InputSource;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Party>();
+}
+
+pub const MAX_PLAYERS: usize = 4;
+
+/// The players that joined on the join screen, in order. Without any, a single player
+/// plays with every device.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Party {
+    members: Vec<InputSource>,
+}
+
+impl Party {
+    pub fn members(&self) -> &[InputSource] {
+        &self.members
+    }
+
+    /// The input source of each player to spawn.
+    pub fn sources(&self) -> Vec<InputSource> {
+        if self.members.is_empty() {
+            vec![InputSource::All]
+        } else {
+            self.members.clone()
+        }
+    }
+
+    pub fn contains(&self, source: InputSource) -> bool {
+        self.members.contains(&source)
+    }
+
+    /// Adds a player playing with the device, unless someone already is or the party is
+    /// full. Returns whether they joined.
+    pub fn join(&mut self, source: InputSource) -> bool {
+        if self.contains(source) || self.members.len() >= MAX_PLAYERS {
+            return false;
+        }
+
+        self.members.push(source);
+        true
+    }
+
+    pub fn leave(&mut self, source: InputSource) {
+        self.members.retain(|member| *member != source);
+    }
+
+    pub fn clear(&mut self) {
+        self.members.clear();
+    }
+}
@@ -57,6 +57,10 @@ fn update_collider_position(query: Query<(&mut Collider, &WorldPosition)>) {
     }
 }
 
+/// Marks a [`Collider`] which only detects overlaps, so other entities pass through it.
+#[derive(Component, Debug, Clone, Copy, Default, Reflect)]
+pub struct Sensor;
+
 #[derive(Component, Debug, Clone)]
 pub struct Collider {
     collider_type: ColliderType,
@@ -121,6 +125,11 @@ impl Collider {
         }
     }
 
+    pub fn set_position(&mut self, position: impl Into<WorldCoords>) {
+        let position = position.into();
+        self.position = Isometry::translation(position.x, position.y, position.z);
+    }
+
     pub fn collider_type(&self) -> &ColliderType {
         &self.collider_type
     }
@@ -15,9 +15,9 @@
 
 use bevy::prelude::*;
 
-use crate::game::character::Facing;
+use crate::game::character::{CharacterState, Facing};
 use crate::game::grid::coords::WorldPosition;
-use crate::game::physics::components::{Collider, CollisionEvent, PhysicsData};
+use crate::game::physics::components::{Collider, CollisionEvent, PhysicsData, Sensor};
 use crate::{AppSystems, PausableSystems};
 
 pub(super) fn plugin(app: &mut App) {
@@ -36,8 +36,7 @@ pub(super) fn plugin(app: &mut App) {
 }
 
 /// These are the movement parameters for our character controller.
-/// For now, this is only used for a single player, but it could power NPCs or
-/// other players as well.
+/// The player sets its intent from input, while enemies set it from their AI.
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 pub struct MovementController {
@@ -60,8 +59,15 @@ impl Default for MovementController {
     }
 }
 
-fn set_intended_velocity(time: Res<Time>, query: Query<(&MovementController, &mut PhysicsData)>) {
-    for (controller, mut physics) in query {
+fn set_intended_velocity(
+    time: Res<Time>,
+    query: Query<(
+        &MovementController,
+        &mut PhysicsData,
+        Option<&CharacterState>,
+    )>,
+) {
+    for (controller, mut physics, state) in query {
         if let PhysicsData::Kinematic {
             ref mut displacement,
             ..
@@ -73,6 +79,11 @@ fn set_intended_velocity(time: Res<Time>, query: Query<(&MovementController, &mu
                 intent *= Vec3::new(1.5, 1.0, 1.5);
             }
 
+            // Dodges move the character on their own, regardless of its intent.
+            if let Some(CharacterState::Dodging { velocity, .. }) = state {
+                intent = velocity * time.delta_secs();
+            }
+
             displacement.x = intent.x;
             displacement.z = intent.z;
             displacement.y += intent.y;
@@ -99,10 +110,22 @@ pub const STEP_UP_HEIGHT: f32 = 0.3;
 
 pub const MAX_STABLE_SLOPE_ANGLE: f32 = 45.0_f32.to_radians();
 
+/// Minimum time in the air before touching the ground again counts as a landing.
+const MIN_LANDING_AIR_TIME: f32 = 0.25;
+
+/// Triggered on a kinematic entity when it touches the ground after being in the air.
+#[derive(EntityEvent, Debug, Clone, Reflect)]
+pub struct LandedEvent {
+    entity: Entity,
+    /// How long the entity was in the air, in seconds.
+    pub air_time: f32,
+}
+
 fn check_collisions(
     time: Res<Time>,
     query: Query<(Entity, &mut PhysicsData, &Collider, &WorldPosition)>,
-    collider_query: Query<(Entity, &Collider)>,
+    collider_query: Query<(Entity, &Collider), Without<Sensor>>,
+    mut commands: Commands,
 ) {
     for (entity, mut physics, collider, position) in query {
         if let PhysicsData::Kinematic {
@@ -145,6 +168,9 @@ fn check_collisions(
                 }
             }
 
+            let was_grounded = *grounded;
+            let air_time = *time_since_grounded;
+
             update_ground_state(
                 ground_normal,
                 displacement,
@@ -155,6 +181,14 @@ fn check_collisions(
                 current_position.y,
                 time.delta_secs(),
             );
+
+            // The air time starts out infinite, so spawning onto the ground is not a landing.
+            if !was_grounded
+                && *grounded
+                && (MIN_LANDING_AIR_TIME..f32::INFINITY).contains(&air_time)
+            {
+                commands.trigger(LandedEvent { entity, air_time });
+            }
         }
     }
 }
@@ -173,7 +207,7 @@ fn handle_collision_response(
     collider: &Collider,
     current_position: Vec3,
     entity: Entity,
-    query2: &Query<(Entity, &Collider)>,
+    query2: &Query<(Entity, &Collider), Without<Sensor>>,
     delta_time: f32,
 ) -> Option<Vec3> {
     let normal = collision.normal();
@@ -213,7 +247,7 @@ fn try_step_up(
     collider: &Collider,
     current_position: Vec3,
     entity: Entity,
-    query2: &Query<(Entity, &Collider)>,
+    query2: &Query<(Entity, &Collider), Without<Sensor>>,
     displacement: &mut Vec3,
     delta_time: f32,
 ) -> bool {
@@ -0,0 +1,314 @@
+//! Pickups lying in the world, which are collected when a player walks over them.
+//!
+//! Pickups are spawned with a [`SpawnPickupEvent`], both for the ones placed in the level
+//! and for drops. Collecting one triggers a [`PickupCollectedEvent`] on the player. Items
+//! given to a character that don't fit into its inventory are dropped as pickups too.
+
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::asset_tracking::LoadResource;
+use crate::audio::sound_effect;
+use crate::game::character::health::{Health, HealthEvent, HealthEventType};
+use crate::game::character::player::Player;
+use crate::game::character::stamina::{Stamina, StaminaEvent, StaminaEventType};
+use crate::game::grid::coords::WorldPosition;
+use crate::game::item::inventory::{
+    Inventory, InventoryAction, InventoryChangedEvent, InventoryEvent, ItemStack,
+};
+use crate::game::item::{ItemAssets, ItemDatabase};
+use crate::game::object::Shadow;
+use crate::game::particle::{ParticleAnimation, ParticleSpawnEvent};
+use crate::game::physics::components::{Collider, Sensor};
+use crate::screens::Screen;
+use crate::{AppSystems, PausableSystems, Scale};
+
+pub(super) fn plugin(app: &mut App) {
+    app.load_resource::<PickupAssets>();
+
+    app.add_systems(
+        Update,
+        (
+            bob_pickups.in_set(AppSystems::Update),
+            collect_pickups.in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    )
+    .add_observer(on_spawn_pickup)
+    .add_observer(on_pickup_collected)
+    .add_observer(drop_overflow);
+}
+
+/// Size of a pickup's collider.
+const PICKUP_SIZE: f32 = 0.3;
+
+/// How far the icon bobs up and down, in pixels.
+const BOB_HEIGHT: f32 = 1.5;
+/// Full bobs per second.
+const BOB_FREQUENCY: f32 = 0.8;
+
+const SPARKLE_FRAMES: usize = 6;
+const SPARKLE_DURATION: f32 = 0.3;
+
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub enum PickupKind {
+    /// Goes into the player's inventory. Left lying if it doesn't fit.
+    Item(ItemStack),
+    /// Heals right away. Left lying if the player is at full health.
+    Heal(usize),
+    /// Restores stamina right away. Left lying if the player's stamina is full.
+    RestoreStamina(usize),
+}
+
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Pickup(pub PickupKind);
+
+/// The icon of a pickup, which bobs up and down above its shadow.
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+struct PickupIcon {
+    elapsed: f32,
+}
+
+/// Spawns a pickup at a position in the world.
+#[derive(Event, Debug, Clone)]
+pub struct SpawnPickupEvent {
+    pub kind: PickupKind,
+    pub position: Vec3,
+}
+
+/// Triggered on a player after it collected a pickup.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct PickupCollectedEvent {
+    entity: Entity,
+    pub kind: PickupKind,
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct PickupAssets {
+    #[dependency]
+    heart: Handle<Image>,
+    #[dependency]
+    stamina_orb: Handle<Image>,
+    #[dependency]
+    shadow: Handle<Image>,
+    #[dependency]
+    sparkle: Handle<Image>,
+    sparkle_layout: Handle<TextureAtlasLayout>,
+    #[dependency]
+    sound: Handle<AudioSource>,
+}
+
+impl FromWorld for PickupAssets {
+    fn from_world(world: &mut World) -> Self {
+        let layout =
+            TextureAtlasLayout::from_grid(UVec2::splat(32), SPARKLE_FRAMES as u32, 1, None, None);
+        let sparkle_layout = world
+            .resource_mut::<Assets<TextureAtlasLayout>>()
+            .add(layout);
+
+        let assets = world.resource::<AssetServer>();
+        Self {
+            heart: assets.load("images/pickups/heart.png"),
+            stamina_orb: assets.load("images/pickups/stamina_orb.png"),
+            shadow: assets.load("images/pickups/pickup_shadow.png"),
+            sparkle: assets.load("images/pickups/sparkle.png"),
+            sparkle_layout,
+            // Placeholder: the button click stands in until there is a pickup sound.
+            sound: assets.load("audio/sound_effects/button_click.ogg"),
+        }
+    }
+}
+
+fn on_spawn_pickup(
+    event: On<SpawnPickupEvent>,
+    scale: Res<Scale>,
+    pickup_assets: Res<PickupAssets>,
+    item_assets: Res<ItemAssets>,
+    databases: Res<Assets<ItemDatabase>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let icon = match &event.kind {
+        PickupKind::Item(stack) => {
+            let definition = databases
+                .get(&item_assets.database)
+                .and_then(|database| database.get(&stack.item));
+
+            let Some(definition) = definition else {
+                warn!("Can't spawn a pickup of unknown item {}", stack.item);
+                return;
+            };
+
+            asset_server.load(&definition.icon)
+        }
+        PickupKind::Heal(_) => pickup_assets.heart.clone(),
+        PickupKind::RestoreStamina(_) => pickup_assets.stamina_orb.clone(),
+    };
+
+    let shadow = pickup_assets.shadow.clone();
+
+    commands.spawn((
+        Name::new("Pickup"),
+        Pickup(event.kind.clone()),
+        WorldPosition(event.position.into()),
+        Transform::from_scale(Vec3::splat(scale.0)),
+        Visibility::default(),
+        Collider::cuboid(Vec3::splat(PICKUP_SIZE), event.position),
+        Sensor,
+        DespawnOnExit(Screen::Gameplay),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((PickupIcon::default(), Sprite::from(icon)));
+            parent.spawn((
+                Shadow,
+                Sprite::from(shadow),
+                Transform::from_translation(Vec3::new(0.0, -9.0, -0.1)),
+            ));
+        })),
+    ));
+}
+
+/// Drops the items that didn't fit into an inventory at the character's feet.
+fn drop_overflow(
+    event: On<InventoryChangedEvent>,
+    position_query: Query<&WorldPosition>,
+    mut commands: Commands,
+) {
+    let InventoryAction::Add(item, _) = &event.action else {
+        return;
+    };
+
+    if event.overflow == 0 {
+        return;
+    }
+
+    let Ok(position) = position_query.get(event.event_target()) else {
+        return;
+    };
+
+    commands.trigger(SpawnPickupEvent {
+        kind: PickupKind::Item(ItemStack::new(item.clone(), event.overflow)),
+        position: position.as_vec3(),
+    });
+}
+
+fn bob_pickups(time: Res<Time>, mut query: Query<(&mut PickupIcon, &mut Transform)>) {
+    for (mut icon, mut transform) in &mut query {
+        icon.elapsed += time.delta_secs();
+        transform.translation.y =
+            BOB_HEIGHT * (icon.elapsed * BOB_FREQUENCY * std::f32::consts::TAU).sin();
+    }
+}
+
+fn collect_pickups(
+    pickup_query: Query<(Entity, &Pickup, &Collider)>,
+    player_query: Query<
+        (
+            Entity,
+            &Collider,
+            Option<&Inventory>,
+            Option<&Health>,
+            Option<&Stamina>,
+        ),
+        With<Player>,
+    >,
+    item_assets: Res<ItemAssets>,
+    databases: Res<Assets<ItemDatabase>>,
+    mut commands: Commands,
+) {
+    let database = databases.get(&item_assets.database);
+    let mut collected_pickups = Vec::new();
+
+    for (player, player_collider, inventory, health, stamina) in &player_query {
+        for (pickup, Pickup(kind), collider) in &pickup_query {
+            if collected_pickups.contains(&pickup)
+                || player_collider.check_collision(collider).is_none()
+            {
+                continue;
+            }
+
+            let collected = match kind {
+                PickupKind::Item(stack) => {
+                    let fits = inventory
+                        .zip(database)
+                        .is_some_and(|(inventory, database)| {
+                            inventory
+                                .space_for(database, &stack.item)
+                                .is_ok_and(|space| space >= stack.count)
+                        });
+                    if fits {
+                        commands.trigger(InventoryEvent::new(
+                            player,
+                            InventoryAction::Add(stack.item.clone(), stack.count),
+                        ));
+                    }
+                    fits
+                }
+                PickupKind::Heal(amount) => {
+                    let hurt = health.is_some_and(|health| !health.is_full());
+                    if hurt {
+                        commands.trigger(HealthEvent::new(player, HealthEventType::Heal(*amount)));
+                    }
+                    hurt
+                }
+                PickupKind::RestoreStamina(amount) => {
+                    let tired = stamina.is_some_and(|stamina| !stamina.is_full());
+                    if tired {
+                        commands.trigger(StaminaEvent::new(
+                            player,
+                            StaminaEventType::Restore(*amount),
+                        ));
+                    }
+                    tired
+                }
+            };
+
+            if !collected {
+                continue;
+            }
+
+            collected_pickups.push(pickup);
+            commands.entity(pickup).despawn();
+            commands.trigger(PickupCollectedEvent {
+                entity: player,
+                kind: kind.clone(),
+            });
+        }
+    }
+}
+
+/// Plays the pickup sound and a sparkle on the player, tinted by what was collected.
+fn on_pickup_collected(
+    event: On<PickupCollectedEvent>,
+    pickup_assets: Res<PickupAssets>,
+    mut commands: Commands,
+) {
+    let color = match event.kind {
+        PickupKind::Item(_) => Color::WHITE,
+        PickupKind::Heal(_) => Color::srgb(1.0, 0.6, 0.6),
+        PickupKind::RestoreStamina(_) => Color::srgb(0.6, 1.0, 0.6),
+    };
+
+    commands.spawn(sound_effect(pickup_assets.sound.clone()));
+    commands.trigger(ParticleSpawnEvent::with_parent(
+        Sprite {
+            color,
+            ..Sprite::from_atlas_image(
+                pickup_assets.sparkle.clone(),
+                TextureAtlas {
+                    layout: pickup_assets.sparkle_layout.clone(),
+                    index: 0,
+                },
+            )
+        },
+        ParticleAnimation::new(
+            0,
+            SPARKLE_FRAMES,
+            Duration::from_secs_f32(SPARKLE_DURATION / SPARKLE_FRAMES as f32),
+        ),
+        event.entity,
+    ));
+}
@@ -0,0 +1,279 @@
+//! Projectiles for ranged attacks, fired by the player and enemies alike.
+
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::asset_tracking::LoadResource;
+use crate::game::character::health::{DamageType, Health, HealthEvent, HealthEventType};
+use crate::game::grid::coords::{ScreenCoords, WorldCoords, WorldPosition};
+use crate::game::physics::components::{Collider, Sensor};
+use crate::screens::Screen;
+use crate::{AppSystems, PausableSystems, Scale};
+
+pub(super) fn plugin(app: &mut App) {
+    app.load_resource::<ProjectileAssets>();
+
+    app.add_systems(
+        Update,
+        (
+            update_projectile_timers.in_set(AppSystems::TickTimers),
+            (move_projectiles, update_projectile_sprite)
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    )
+    .add_observer(on_spawn_projectile);
+}
+
+/// Size of the cube used for projectile collisions.
+const PROJECTILE_SIZE: f32 = 0.2;
+/// Longest distance a projectile moves between collision checks, so fast projectiles
+/// don't pass through thin colliders.
+const MAX_STEP: f32 = 0.1;
+
+/// Everything needed to fire a kind of projectile.
+#[derive(Debug, Clone, Reflect)]
+pub struct ProjectileDefinition {
+    /// Speed in world units per second.
+    pub speed: f32,
+    /// Downwards acceleration in world units per second squared. Zero flies straight.
+    pub gravity: f32,
+    /// Seconds before the projectile disappears, including time spent stuck in terrain.
+    pub lifetime: f32,
+    pub damage: usize,
+    pub damage_type: DamageType,
+    pub on_terrain: TerrainImpact,
+    pub sprite: ProjectileSprite,
+}
+
+/// A horizontal strip of animation frames.
+#[derive(Debug, Clone, Reflect)]
+pub struct ProjectileSprite {
+    pub image: Handle<Image>,
+    /// Shared layout of the strip, so firing doesn't create a new one for every shot.
+    pub layout: Handle<TextureAtlasLayout>,
+    pub frames: usize,
+    pub interval: Duration,
+    /// Screen space angle the image points at, in radians counterclockwise from the right.
+    pub angle: f32,
+    /// Size relative to the game's [`Scale`].
+    pub scale: f32,
+}
+
+/// What a projectile does when it hits something without [`Health`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum TerrainImpact {
+    Despawn,
+    /// Stays where it hit until its lifetime runs out.
+    Stick,
+}
+
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Projectile {
+    /// The entity that fired the projectile, which it can not hit.
+    pub owner: Entity,
+    /// Velocity in world units per second.
+    pub velocity: Vec3,
+    gravity: f32,
+    damage: usize,
+    damage_type: DamageType,
+    on_terrain: TerrainImpact,
+    lifetime: Timer,
+    stuck: bool,
+    animation_timer: Timer,
+    frames: usize,
+    frame: usize,
+    angle: f32,
+}
+
+/// Lets an entity fire projectiles, e.g. from its behaviour tree.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct RangedAttack {
+    pub projectile: ProjectileDefinition,
+    /// Length of the shooting animation in seconds.
+    pub duration: f32,
+}
+
+/// Fires a projectile from `position` in `direction`.
+#[derive(Event, Debug, Clone)]
+pub struct SpawnProjectileEvent {
+    pub owner: Entity,
+    pub position: Vec3,
+    pub direction: Vec3,
+    pub definition: ProjectileDefinition,
+}
+
+fn on_spawn_projectile(event: On<SpawnProjectileEvent>, scale: Res<Scale>, mut commands: Commands) {
+    let definition = &event.definition;
+    let sprite = &definition.sprite;
+
+    commands.spawn((
+        Name::new("Projectile"),
+        Projectile {
+            owner: event.owner,
+            velocity: event.direction.normalize_or_zero() * definition.speed,
+            gravity: definition.gravity,
+            damage: definition.damage,
+            damage_type: definition.damage_type,
+            on_terrain: definition.on_terrain,
+            lifetime: Timer::from_seconds(definition.lifetime, TimerMode::Once),
+            stuck: false,
+            animation_timer: Timer::new(sprite.interval, TimerMode::Repeating),
+            frames: sprite.frames,
+            frame: 0,
+            angle: sprite.angle,
+        },
+        WorldPosition(event.position.into()),
+        Collider::cuboid(Vec3::splat(PROJECTILE_SIZE), event.position),
+        Sensor,
+        Transform::from_scale(Vec3::splat(scale.0 * sprite.scale)),
+        Sprite::from_atlas_image(
+            sprite.image.clone(),
+            TextureAtlas {
+                layout: sprite.layout.clone(),
+                index: 0,
+            },
+        ),
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
+
+fn update_projectile_timers(
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Projectile)>,
+    mut commands: Commands,
+) {
+    for (entity, mut projectile) in &mut query {
+        projectile.lifetime.tick(time.delta());
+        projectile.animation_timer.tick(time.delta());
+
+        if projectile.lifetime.is_finished() {
+            commands.entity(entity).try_despawn();
+        }
+    }
+}
+
+fn move_projectiles(
+    time: Res<Time>,
+    mut projectile_query: Query<(Entity, &mut Projectile, &mut WorldPosition, &mut Collider)>,
+    collider_query: Query<(Entity, &Collider, Has<Health>), (Without<Projectile>, Without<Sensor>)>,
+    mut commands: Commands,
+) {
+    for (entity, mut projectile, mut position, mut collider) in &mut projectile_query {
+        // Expired projectiles are already being despawned and shouldn't hit anything.
+        if projectile.stuck || projectile.lifetime.is_finished() {
+            continue;
+        }
+
+        projectile.velocity.y -= projectile.gravity * time.delta_secs();
+
+        let displacement = projectile.velocity * time.delta_secs();
+        let steps = (displacement.length() / MAX_STEP).ceil().max(1.0) as usize;
+
+        'steps: for _ in 0..steps {
+            let new_position = position.as_vec3() + displacement / steps as f32;
+            position.set(new_position);
+            collider.set_position(new_position);
+
+            for (other, other_collider, has_health) in &collider_query {
+                if other == projectile.owner || collider.check_collision(other_collider).is_none() {
+                    continue;
+                }
+
+                if has_health {
+                    commands.trigger(
+                        HealthEvent::new(
+                            other,
+                            HealthEventType::Damage(projectile.damage, projectile.damage_type),
+                        )
+                        .with_source(projectile.owner)
+                        .ranged(),
+                    );
+                    commands.entity(entity).try_despawn();
+                } else {
+                    match projectile.on_terrain {
+                        TerrainImpact::Despawn => commands.entity(entity).try_despawn(),
+                        TerrainImpact::Stick => projectile.stuck = true,
+                    }
+                }
+
+                break 'steps;
+            }
+        }
+    }
+}
+
+fn update_projectile_sprite(
+    mut query: Query<(&mut Projectile, &WorldPosition, &mut Transform, &mut Sprite)>,
+) {
+    for (mut projectile, position, mut transform, mut sprite) in &mut query {
+        if projectile.animation_timer.is_finished() && !projectile.stuck {
+            projectile.frame = (projectile.frame + 1) % projectile.frames.max(1);
+        }
+
+        if let Some(atlas) = sprite.texture_atlas.as_mut() {
+            atlas.index = projectile.frame;
+        }
+
+        if projectile.stuck {
+            continue;
+        }
+
+        // Point the sprite along the direction of flight as seen on screen.
+        let from = ScreenCoords::from(WorldCoords(position.as_vec3()));
+        let to = ScreenCoords::from(WorldCoords(position.as_vec3() + projectile.velocity));
+        let direction = (to.0 - from.0).xy();
+
+        if direction.length() > 1e-6 {
+            let angle = direction.y.atan2(direction.x) - projectile.angle;
+            transform.rotation = Quat::from_rotation_z(angle);
+        }
+    }
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct ProjectileAssets {
+    #[dependency]
+    pub knife: Handle<Image>,
+    pub knife_layout: Handle<TextureAtlasLayout>,
+    #[dependency]
+    pub bolt: Handle<Image>,
+    pub bolt_layout: Handle<TextureAtlasLayout>,
+}
+
+pub const KNIFE_FRAMES: usize = 1;
+pub const BOLT_FRAMES: usize = 7;
+
+impl FromWorld for ProjectileAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mut layouts = world.resource_mut::<Assets<TextureAtlasLayout>>();
+        let knife_layout = layouts.add(TextureAtlasLayout::from_grid(
+            UVec2::splat(32),
+            KNIFE_FRAMES as u32,
+            1,
+            None,
+            None,
+        ));
+        let bolt_layout = layouts.add(TextureAtlasLayout::from_grid(
+            UVec2::splat(96),
+            BOLT_FRAMES as u32,
+            1,
+            None,
+            None,
+        ));
+
+        let assets = world.resource::<AssetServer>();
+        Self {
+            // Placeholder until there is a dedicated throwing knife sprite.
+            knife: assets.load("images/sword.png"),
+            knife_layout,
+            bolt: assets.load("images/characters/attack_particle.png"),
+            bolt_layout,
+        }
+    }
+}
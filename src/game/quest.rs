@@ -0,0 +1,580 @@
+//! Quests, defined in `.quests.ron` asset files.
+//!
+//! A quest is a list of stages, each with objectives that all have to be completed before
+//! the quest moves on to the next stage. Objectives refer to things in the world by their
+//! [`QuestTarget`] and advance from gameplay events on their own. Quests are started with
+//! a [`StartQuestEvent`] and their state lives in the [`QuestLog`], which only holds plain
+//! data so it can be saved and loaded as is.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::asset_tracking::{LoadResource, RegisterRonAsset};
+use crate::game::character::health::DeathEvent;
+use crate::game::character::player::Player;
+use crate::game::flags::SetFlagEvent;
+use crate::game::grid::coords::WorldPosition;
+use crate::game::interaction::InteractEvent;
+use crate::game::item::ItemId;
+use crate::game::item::inventory::{Inventory, InventoryAction, InventoryEvent};
+use crate::{AppSystems, PausableSystems};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ron_asset::<QuestDatabase>(&["quests.ron"]);
+    app.load_resource::<QuestAssets>();
+    app.init_resource::<QuestLog>();
+
+    app.add_systems(
+        Update,
+        track_objectives
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+
+    app.add_observer(on_start_quest)
+        .add_observer(on_quest_completed)
+        .add_observer(on_target_death)
+        .add_observer(on_target_interact);
+}
+
+/// All quests, as written in an asset file.
+#[derive(Asset, TypePath, Debug, Clone, Default, Deserialize)]
+pub struct QuestDatabase {
+    pub quests: Vec<QuestDefinition>,
+}
+
+impl QuestDatabase {
+    pub fn get(&self, id: &str) -> Option<&QuestDefinition> {
+        self.quests.iter().find(|quest| quest.id == id)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuestDefinition {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub stages: Vec<QuestStage>,
+    /// Handed out once the last stage is done.
+    #[serde(default)]
+    pub rewards: Vec<QuestReward>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuestStage {
+    /// What the player is asked to do, e.g. "Clear out the yard".
+    pub description: String,
+    pub objectives: Vec<Objective>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum Objective {
+    /// Kill this many entities with the [`QuestTarget`] while the stage is active.
+    Kill { target: String, count: u32 },
+    /// Walk into the [`QuestArea`] with this [`QuestTarget`].
+    Reach { area: String },
+    /// Carry this many of the item. Dropping or using them loses the progress again.
+    Collect { item: ItemId, count: u32 },
+    /// Interact with the entity with this [`QuestTarget`].
+    TalkTo { target: String },
+}
+
+impl Objective {
+    /// How much progress completes the objective.
+    pub fn required(&self) -> u32 {
+        match self {
+            Objective::Kill { count, .. } | Objective::Collect { count, .. } => *count,
+            Objective::Reach { .. } | Objective::TalkTo { .. } => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum QuestReward {
+    /// Given to every player.
+    Item(ItemId, u32),
+    SetFlag(String),
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct QuestAssets {
+    #[dependency]
+    pub database: Handle<QuestDatabase>,
+}
+
+impl FromWorld for QuestAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            database: assets.load("quests/base.quests.ron"),
+        }
+    }
+}
+
+/// Names an entity for quest objectives, e.g. `"ducky"` or `"enemy"`. Several entities
+/// may share a name.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct QuestTarget(pub String);
+
+impl QuestTarget {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+}
+
+/// A place players can be sent to by a [`Objective::Reach`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct QuestArea {
+    /// How close a player has to get, in world units.
+    pub radius: f32,
+}
+
+/// An invisible area named for [`Objective::Reach`] objectives.
+pub fn quest_area(name: impl Into<String>, position: Vec3, radius: f32) -> impl Bundle {
+    let name = name.into();
+
+    (
+        Name::new(format!("Quest Area {name}")),
+        QuestTarget(name),
+        QuestArea { radius },
+        WorldPosition(position.into()),
+    )
+}
+
+/// Every quest that has been started, in the order they were started.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct QuestLog {
+    pub quests: Vec<QuestState>,
+}
+
+impl QuestLog {
+    pub fn get(&self, id: &str) -> Option<&QuestState> {
+        self.quests.iter().find(|quest| quest.id == id)
+    }
+
+    pub fn active(&self) -> impl Iterator<Item = &QuestState> {
+        self.quests.iter().filter(|quest| !quest.completed)
+    }
+
+    pub fn completed(&self) -> impl Iterator<Item = &QuestState> {
+        self.quests.iter().filter(|quest| quest.completed)
+    }
+
+    /// Updates the progress of every objective in the current stage of the active quests.
+    /// `update` gets each objective with its progress and returns the new progress, if it
+    /// changed. Returns whether anything changed, and triggers a [`QuestCompletedEvent`]
+    /// for every quest that was finished by it.
+    fn advance(
+        &mut self,
+        database: &QuestDatabase,
+        commands: &mut Commands,
+        mut update: impl FnMut(&Objective, u32) -> Option<u32>,
+    ) -> bool {
+        let mut changed = false;
+
+        for quest in self.quests.iter_mut().filter(|quest| !quest.completed) {
+            let Some(definition) = database.get(&quest.id) else {
+                continue;
+            };
+
+            let Some(stage) = definition.stages.get(quest.stage) else {
+                continue;
+            };
+
+            for (objective, progress) in stage.objectives.iter().zip(&mut quest.progress) {
+                if let Some(new) = update(objective, *progress)
+                    .map(|new| new.min(objective.required()))
+                    .filter(|new| new != progress)
+                {
+                    *progress = new;
+                    changed = true;
+                }
+            }
+
+            let stage_done = stage
+                .objectives
+                .iter()
+                .zip(&quest.progress)
+                .all(|(objective, progress)| *progress >= objective.required());
+
+            if !stage_done {
+                continue;
+            }
+
+            quest.stage += 1;
+            match definition.stages.get(quest.stage) {
+                Some(next) => quest.progress = vec![0; next.objectives.len()],
+                None => {
+                    quest.progress.clear();
+                    quest.completed = true;
+                    commands.trigger(QuestCompletedEvent {
+                        quest: quest.id.clone(),
+                    });
+                }
+            }
+            changed = true;
+        }
+
+        changed
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub struct QuestState {
+    pub id: String,
+    /// Index of the current stage in the quest's definition.
+    pub stage: usize,
+    /// Progress of each objective in the current stage.
+    pub progress: Vec<u32>,
+    pub completed: bool,
+}
+
+impl QuestState {
+    /// What to do next, e.g. "Defeat the locals in the yard (1/2)", or nothing once the
+    /// quest is completed.
+    pub fn objective_text(&self, definition: &QuestDefinition) -> Option<String> {
+        let stage = definition
+            .stages
+            .get(self.stage)
+            .filter(|_| !self.completed)?;
+
+        let required: u32 = stage.objectives.iter().map(Objective::required).sum();
+        let progress: u32 = stage
+            .objectives
+            .iter()
+            .zip(&self.progress)
+            .map(|(objective, progress)| (*progress).min(objective.required()))
+            .sum();
+
+        Some(if required > 1 {
+            format!("{} ({progress}/{required})", stage.description)
+        } else {
+            stage.description.clone()
+        })
+    }
+}
+
+/// Adds the quest to the [`QuestLog`], unless it was started before.
+#[derive(Event, Debug, Clone)]
+pub struct StartQuestEvent(pub String);
+
+/// Triggered when the last stage of a quest is done, which hands out its rewards.
+#[derive(Event, Debug, Clone)]
+pub struct QuestCompletedEvent {
+    pub quest: String,
+}
+
+fn on_start_quest(
+    event: On<StartQuestEvent>,
+    quest_assets: Res<QuestAssets>,
+    databases: Res<Assets<QuestDatabase>>,
+    mut log: ResMut<QuestLog>,
+) {
+    let Some(database) = databases.get(&quest_assets.database) else {
+        return;
+    };
+
+    let Some(definition) = database.get(&event.0) else {
+        warn!("No quest with id {}", event.0);
+        return;
+    };
+
+    if log.get(&event.0).is_some() {
+        return;
+    }
+
+    log.quests.push(QuestState {
+        id: definition.id.clone(),
+        stage: 0,
+        progress: vec![
+            0;
+            definition
+                .stages
+                .first()
+                .map_or(0, |stage| stage.objectives.len())
+        ],
+        completed: definition.stages.is_empty(),
+    });
+}
+
+fn on_quest_completed(
+    event: On<QuestCompletedEvent>,
+    quest_assets: Res<QuestAssets>,
+    databases: Res<Assets<QuestDatabase>>,
+    player_query: Query<Entity, With<Player>>,
+    mut commands: Commands,
+) {
+    let Some(definition) = databases
+        .get(&quest_assets.database)
+        .and_then(|database| database.get(&event.quest))
+    else {
+        return;
+    };
+
+    for reward in &definition.rewards {
+        match reward {
+            QuestReward::Item(item, count) => {
+                for player in &player_query {
+                    commands.trigger(InventoryEvent::new(
+                        player,
+                        InventoryAction::Add(item.clone(), *count),
+                    ));
+                }
+            }
+            QuestReward::SetFlag(flag) => commands.trigger(SetFlagEvent {
+                flag: flag.clone(),
+                value: true,
+            }),
+        }
+    }
+}
+
+/// Only counts towards the current stage, so kills from before it started don't.
+fn on_target_death(
+    event: On<DeathEvent>,
+    target_query: Query<&QuestTarget>,
+    quest_assets: Res<QuestAssets>,
+    databases: Res<Assets<QuestDatabase>>,
+    mut log: ResMut<QuestLog>,
+    mut commands: Commands,
+) {
+    let Ok(QuestTarget(name)) = target_query.get(event.event_target()) else {
+        return;
+    };
+
+    let Some(database) = databases.get(&quest_assets.database) else {
+        return;
+    };
+
+    log.advance(
+        database,
+        &mut commands,
+        |objective, progress| match objective {
+            Objective::Kill { target, .. } if target == name => Some(progress + 1),
+            _ => None,
+        },
+    );
+}
+
+fn on_target_interact(
+    event: On<InteractEvent>,
+    target_query: Query<&QuestTarget>,
+    quest_assets: Res<QuestAssets>,
+    databases: Res<Assets<QuestDatabase>>,
+    mut log: ResMut<QuestLog>,
+    mut commands: Commands,
+) {
+    let Ok(QuestTarget(name)) = target_query.get(event.event_target()) else {
+        return;
+    };
+
+    let Some(database) = databases.get(&quest_assets.database) else {
+        return;
+    };
+
+    log.advance(database, &mut commands, |objective, _| match objective {
+        Objective::TalkTo { target } if target == name => Some(1),
+        _ => None,
+    });
+}
+
+/// Reaching areas and collecting items aren't tied to a single event, so they are checked
+/// every frame instead.
+fn track_objectives(
+    player_query: Query<(&WorldPosition, Option<&Inventory>), With<Player>>,
+    area_query: Query<(&QuestTarget, &QuestArea, &WorldPosition)>,
+    quest_assets: Res<QuestAssets>,
+    databases: Res<Assets<QuestDatabase>>,
+    mut log: ResMut<QuestLog>,
+    mut commands: Commands,
+) {
+    let Some(database) = databases.get(&quest_assets.database) else {
+        return;
+    };
+
+    let reached = |area: &str| {
+        area_query
+            .iter()
+            .filter(|(QuestTarget(name), ..)| name == area)
+            .any(|(_, QuestArea { radius }, area_position)| {
+                player_query.iter().any(|(position, _)| {
+                    position.as_vec3().distance(area_position.as_vec3()) <= *radius
+                })
+            })
+    };
+
+    let carried = |item: &ItemId| -> u32 {
+        player_query
+            .iter()
+            .filter_map(|(_, inventory)| inventory)
+            .map(|inventory| inventory.count(item))
+            .sum()
+    };
+
+    // Only flag the log as changed when progress actually moved.
+    let changed = log
+        .bypass_change_detection()
+        .advance(database, &mut commands, |objective, _| match objective {
+            Objective::Reach { area } => reached(area).then_some(1),
+            Objective::Collect { item, .. } => Some(carried(item)),
+            _ => None,
+        });
+
+    if changed {
+        log.set_changed();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn database() -> QuestDatabase {
+        let stage = |objectives| QuestStage {
+            description: String::new(),
+            objectives,
+        };
+
+        QuestDatabase {
+            quests: vec![QuestDefinition {
+                id: "pest_control".to_string(),
+                name: "Pest Control".to_string(),
+                description: String::new(),
+                stages: vec![
+                    stage(vec![Objective::TalkTo {
+                        target: "ducky".to_string(),
+                    }]),
+                    stage(vec![
+                        Objective::Kill {
+                            target: "enemy".to_string(),
+                            count: 2,
+                        },
+                        Objective::Reach {
+                            area: "yard".to_string(),
+                        },
+                    ]),
+                ],
+                rewards: Vec::new(),
+            }],
+        }
+    }
+
+    fn started() -> QuestLog {
+        QuestLog {
+            quests: vec![QuestState {
+                id: "pest_control".to_string(),
+                stage: 0,
+                progress: vec![0],
+                completed: false,
+            }],
+        }
+    }
+
+    /// Runs [`QuestLog::advance`] and returns whether anything changed and which quests
+    /// were completed.
+    fn advance(
+        log: &mut QuestLog,
+        update: impl FnMut(&Objective, u32) -> Option<u32>,
+    ) -> (bool, Vec<String>) {
+        #[derive(Resource, Default)]
+        struct Completed(Vec<String>);
+
+        let mut world = World::new();
+        world.init_resource::<Completed>();
+        world.add_observer(
+            |event: On<QuestCompletedEvent>, mut completed: ResMut<Completed>| {
+                completed.0.push(event.quest.clone());
+            },
+        );
+
+        let changed = log.advance(&database(), &mut world.commands(), update);
+        world.flush();
+
+        (changed, world.remove_resource::<Completed>().unwrap().0)
+    }
+
+    fn kill(target: &str) -> impl FnMut(&Objective, u32) -> Option<u32> {
+        move |objective, progress| match objective {
+            Objective::Kill { target: killed, .. } if killed == target => Some(progress + 1),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn finishing_a_stage_moves_on_to_the_next() {
+        let mut log = started();
+
+        let (changed, completed) = advance(&mut log, |objective, _| match objective {
+            Objective::TalkTo { target } if target == "ducky" => Some(1),
+            _ => None,
+        });
+
+        assert!(changed);
+        assert!(completed.is_empty());
+        assert_eq!(log.quests[0].stage, 1);
+        assert_eq!(log.quests[0].progress, [0, 0]);
+    }
+
+    #[test]
+    fn kills_only_count_towards_the_current_stage() {
+        let mut log = started();
+
+        assert_eq!(advance(&mut log, kill("enemy")), (false, Vec::new()));
+
+        log.quests[0].stage = 1;
+        log.quests[0].progress = vec![0, 0];
+        advance(&mut log, kill("enemy"));
+        advance(&mut log, kill("ducky"));
+
+        assert_eq!(log.quests[0].progress, [1, 0]);
+    }
+
+    #[test]
+    fn progress_is_capped_at_the_required_amount() {
+        let mut log = started();
+        log.quests[0].stage = 1;
+        log.quests[0].progress = vec![2, 0];
+
+        assert_eq!(advance(&mut log, kill("enemy")), (false, Vec::new()));
+        assert_eq!(log.quests[0].progress, [2, 0]);
+    }
+
+    #[test]
+    fn finishing_the_last_stage_completes_the_quest() {
+        let mut log = started();
+        log.quests[0].stage = 1;
+        log.quests[0].progress = vec![1, 1];
+
+        let (changed, completed) = advance(&mut log, kill("enemy"));
+
+        assert!(changed);
+        assert_eq!(completed, ["pest_control"]);
+        assert!(log.quests[0].completed);
+        assert!(log.quests[0].progress.is_empty());
+
+        // Completed quests don't advance any further.
+        assert_eq!(advance(&mut log, kill("enemy")), (false, Vec::new()));
+    }
+
+    #[test]
+    fn quest_log_round_trips() {
+        let log = QuestLog {
+            quests: vec![QuestState {
+                id: "pest_control".to_string(),
+                stage: 0,
+                progress: vec![1, 0],
+                completed: false,
+            }],
+        };
+
+        let saved = ron::to_string(&log).unwrap();
+        let loaded: QuestLog = ron::from_str(&saved).unwrap();
+
+        assert_eq!(loaded, log);
+    }
+}
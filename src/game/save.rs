@@ -0,0 +1,121 @@
+//! Saves the player's progress, i.e. the [`QuestLog`] and [`GameFlags`], and loads it again
+//! when gameplay starts.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::flags::GameFlags;
+use crate::game::quest::QuestLog;
+use crate::screens::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Gameplay), load_progress);
+    app.add_systems(
+        Update,
+        save_progress
+            .run_if(in_state(Screen::Gameplay))
+            .run_if(resource_changed::<QuestLog>.or(resource_changed::<GameFlags>)),
+    );
+}
+
+const SAVE_PATH: &str = "saves/progress.ron";
+
+#[derive(thiserror::Error, Debug)]
+pub enum SaveError {
+    #[error("could not access the save file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not read the save file: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("could not write the save file: {0}")]
+    Write(#[from] ron::Error),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Progress {
+    quests: QuestLog,
+    flags: GameFlags,
+}
+
+impl Progress {
+    #[cfg(not(target_family = "wasm"))]
+    fn read() -> Result<Option<Self>, SaveError> {
+        match std::fs::read_to_string(SAVE_PATH) {
+            Ok(text) => Ok(Some(ron::from_str(&text)?)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn save(&self) -> Result<(), SaveError> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+
+        if let Some(directory) = std::path::Path::new(SAVE_PATH).parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+
+        Ok(std::fs::write(SAVE_PATH, text)?)
+    }
+
+    // There is no file system on the web, so progress only lasts until the page is closed.
+    #[cfg(target_family = "wasm")]
+    fn read() -> Result<Option<Self>, SaveError> {
+        Ok(None)
+    }
+
+    #[cfg(target_family = "wasm")]
+    fn save(&self) -> Result<(), SaveError> {
+        Ok(())
+    }
+}
+
+/// Starts from the saved progress, or from scratch if there is none.
+fn load_progress(mut log: ResMut<QuestLog>, mut flags: ResMut<GameFlags>) {
+    let progress = match Progress::read() {
+        Ok(progress) => progress.unwrap_or_default(),
+        Err(error) => {
+            warn!("Starting without saved progress: {error}");
+            Progress::default()
+        }
+    };
+
+    *log = progress.quests;
+    *flags = progress.flags;
+}
+
+fn save_progress(log: Res<QuestLog>, flags: Res<GameFlags>) {
+    let progress = Progress {
+        quests: log.clone(),
+        flags: flags.clone(),
+    };
+
+    if let Err(error) = progress.save() {
+        warn!("Failed to save progress: {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::quest::QuestState;
+
+    #[test]
+    fn progress_round_trips() {
+        let progress = Progress {
+            quests: QuestLog {
+                quests: vec![QuestState {
+                    id: "pest_control".to_string(),
+                    stage: 1,
+                    progress: vec![2],
+                    completed: false,
+                }],
+            },
+            flags: GameFlags::default(),
+        };
+
+        let saved = ron::to_string(&progress).unwrap();
+        let loaded: Progress = ron::from_str(&saved).unwrap();
+
+        assert_eq!(loaded, progress);
+    }
+}
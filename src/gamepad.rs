@@ -5,10 +5,17 @@ use bevy::prelude::*;
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         Update,
-        (gamepad_connections.in_set(AppSystems::RecordInput),),
+        (gamepad_connections, switch_to_last_used_gamepad)
+            .chain()
+            .in_set(AppSystems::RecordInput),
     );
 }
 
+/// How far a stick on another gamepad has to be pushed to switch to that gamepad, so a
+/// drifting stick doesn't steal control.
+const SWITCH_STICK_THRESHOLD: f32 = 0.5;
+
+/// The gamepad that controls the game. Whichever connected gamepad was used last wins.
 #[derive(Resource)]
 pub struct GamepadRes(pub Entity);
 
@@ -38,29 +45,40 @@ pub fn gamepad_connections(
                 }
             }
             GamepadConnection::Disconnected => {
-                // if it's the one we previously used for the player, remove it:
+                // if it's the one we previously used for the player, fall back to another
+                // connected gamepad, or remove it if there is none:
                 if let Some(GamepadRes(old_id)) = my_gamepad.as_deref()
                     && *old_id == ev_conn.gamepad
                 {
-                    commands.remove_resource::<GamepadRes>();
+                    match gamepads.iter().find(|gamepad| *gamepad != ev_conn.gamepad) {
+                        Some(fallback) => commands.insert_resource(GamepadRes(fallback)),
+                        None => commands.remove_resource::<GamepadRes>(),
+                    }
                 }
-
-                //TODO: fallback to another gamepad if this one is disconnected
             }
         }
     }
 }
 
-pub fn gamepad_just_pressed(button: GamepadButton) -> impl SystemCondition<()> {
-    IntoSystem::into_system(
-        move |gamepad_res: Option<Res<GamepadRes>>, gamepad: Query<&Gamepad>| {
-            if let Some(gamepad_res) = gamepad_res
-                && let Ok(gamepad) = gamepad.get(gamepad_res.0)
-            {
-                return gamepad.just_pressed(button);
-            }
+/// Switches to another connected gamepad as soon as any of its buttons is pressed or a
+/// stick is pushed.
+fn switch_to_last_used_gamepad(
+    mut commands: Commands,
+    my_gamepad: Option<Res<GamepadRes>>,
+    gamepads: Query<(Entity, &Gamepad)>,
+) {
+    let Some(GamepadRes(current)) = my_gamepad.as_deref() else {
+        return;
+    };
 
-            false
-        },
-    )
+    let used = gamepads.iter().find(|(entity, gamepad)| {
+        entity != current
+            && (gamepad.get_just_pressed().next().is_some()
+                || gamepad.left_stick().length() > SWITCH_STICK_THRESHOLD
+                || gamepad.right_stick().length() > SWITCH_STICK_THRESHOLD)
+    });
+
+    if let Some((entity, _)) = used {
+        commands.insert_resource(GamepadRes(entity));
+    }
 }
@@ -0,0 +1,1076 @@
+//! Maps keyboard, mouse and gamepad input to game [`Action`]s.
+//!
+//! Gameplay and menus only ask the [`ActionState`] about actions, never the devices
+//! themselves. Menus use the [`ActionState`] resource, which reads every device, while each
+//! player has their own that only reads the devices in their [`InputSource`]. Which inputs
+//! trigger which action is up to the [`InputMap`], which can be changed at runtime and is
+//! saved to a config file whenever it is. Inserting a [`Rebinding`] binds an action to
+//! whatever is pressed next.
+//!
+//! Stick positions go through their [`StickSettings`] before they count towards any action,
+//! so movement and aim share the same deadzones and response curves.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use bevy::input::gamepad::{AxisSettings, GamepadSettings};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::gamepad::GamepadRes;
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(InputMap::load());
+    app.init_resource::<ActionState>();
+
+    app.add_systems(
+        PreUpdate,
+        update_action_state
+            .in_set(UpdateActions)
+            .after(bevy::input::InputSystems),
+    );
+    app.add_systems(
+        PreUpdate,
+        disable_axial_deadzones.before(bevy::input::InputSystems),
+    );
+    app.add_systems(
+        Update,
+        save_input_map.run_if(resource_changed::<InputMap>.and(not(resource_added::<InputMap>))),
+    );
+}
+
+/// Where the [`InputMap`] is saved, relative to the working directory.
+const INPUT_MAP_PATH: &str = "config/input.ron";
+
+/// How far an axis other than the sticks, e.g. an analog trigger, has to be pushed before
+/// it counts as pressed. The sticks use their [`StickSettings`] instead.
+const AXIS_THRESHOLD: f32 = 0.1;
+/// How far a stick has to be pushed to be picked up by a [`Rebinding`], so a stick that
+/// is resting slightly off-center isn't bound by accident.
+const REBIND_AXIS_THRESHOLD: f32 = 0.5;
+/// Cancels a [`Rebinding`], and so can't be bound itself.
+const CANCEL_REBIND_KEY: KeyCode = KeyCode::Escape;
+/// Cancels a [`Rebinding`] from any gamepad, and so can't be bound itself.
+const CANCEL_REBIND_BUTTON: GamepadButton = GamepadButton::Select;
+
+const STICK_AXES: [GamepadAxis; 4] = [
+    GamepadAxis::LeftStickX,
+    GamepadAxis::LeftStickY,
+    GamepadAxis::RightStickX,
+    GamepadAxis::RightStickY,
+];
+
+/// Updates the [`ActionState`] from this frame's input. Runs in `PreUpdate`.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UpdateActions;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Reflect, Serialize, Deserialize,
+)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Jump,
+    ToggleRun,
+    AimUp,
+    AimDown,
+    AimLeft,
+    AimRight,
+    Attack,
+    Block,
+    Throw,
+    Dodge,
+    QuickHeal,
+    Interact,
+    DebugDamage,
+    DebugHeal,
+    /// Opens the pause menu, or closes any open menu.
+    Pause,
+    NavigateUp,
+    NavigateDown,
+    NavigateLeft,
+    NavigateRight,
+    /// Clicks the focused menu button.
+    Select,
+    /// Leaves the current menu.
+    Back,
+}
+
+impl Action {
+    pub const ALL: [Action; 25] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Jump,
+        Action::ToggleRun,
+        Action::AimUp,
+        Action::AimDown,
+        Action::AimLeft,
+        Action::AimRight,
+        Action::Attack,
+        Action::Block,
+        Action::Throw,
+        Action::Dodge,
+        Action::QuickHeal,
+        Action::Interact,
+        Action::DebugDamage,
+        Action::DebugHeal,
+        Action::Pause,
+        Action::NavigateUp,
+        Action::NavigateDown,
+        Action::NavigateLeft,
+        Action::NavigateRight,
+        Action::Select,
+        Action::Back,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Jump => "Jump",
+            Action::ToggleRun => "Toggle run",
+            Action::AimUp => "Aim up",
+            Action::AimDown => "Aim down",
+            Action::AimLeft => "Aim left",
+            Action::AimRight => "Aim right",
+            Action::Attack => "Attack",
+            Action::Block => "Block",
+            Action::Throw => "Throw",
+            Action::Dodge => "Dodge",
+            Action::QuickHeal => "Quick heal",
+            Action::Interact => "Interact",
+            Action::DebugDamage => "Debug damage",
+            Action::DebugHeal => "Debug heal",
+            Action::Pause => "Pause",
+            Action::NavigateUp => "Menu up",
+            Action::NavigateDown => "Menu down",
+            Action::NavigateLeft => "Menu left",
+            Action::NavigateRight => "Menu right",
+            Action::Select => "Menu select",
+            Action::Back => "Menu back",
+        }
+    }
+
+    pub fn group(&self) -> ActionGroup {
+        match self {
+            Action::NavigateUp
+            | Action::NavigateDown
+            | Action::NavigateLeft
+            | Action::NavigateRight
+            | Action::Select
+            | Action::Back => ActionGroup::Menu,
+            Action::Pause => ActionGroup::Global,
+            _ => ActionGroup::Gameplay,
+        }
+    }
+
+    fn default_bindings(&self) -> Vec<Binding> {
+        use Binding::*;
+
+        match self {
+            Action::MoveUp => vec![
+                Key(KeyCode::KeyW),
+                Key(KeyCode::ArrowUp),
+                Axis(GamepadAxis::LeftStickY, AxisDirection::Positive),
+            ],
+            Action::MoveDown => vec![
+                Key(KeyCode::KeyS),
+                Key(KeyCode::ArrowDown),
+                Axis(GamepadAxis::LeftStickY, AxisDirection::Negative),
+            ],
+            Action::MoveLeft => vec![
+                Key(KeyCode::KeyA),
+                Key(KeyCode::ArrowLeft),
+                Axis(GamepadAxis::LeftStickX, AxisDirection::Negative),
+            ],
+            Action::MoveRight => vec![
+                Key(KeyCode::KeyD),
+                Key(KeyCode::ArrowRight),
+                Axis(GamepadAxis::LeftStickX, AxisDirection::Positive),
+            ],
+            Action::Jump => vec![Key(KeyCode::Space), Gamepad(GamepadButton::South)],
+            Action::ToggleRun => vec![Key(KeyCode::ShiftLeft), Gamepad(GamepadButton::LeftThumb)],
+            Action::AimUp => vec![Axis(GamepadAxis::RightStickY, AxisDirection::Positive)],
+            Action::AimDown => vec![Axis(GamepadAxis::RightStickY, AxisDirection::Negative)],
+            Action::AimLeft => vec![Axis(GamepadAxis::RightStickX, AxisDirection::Negative)],
+            Action::AimRight => vec![Axis(GamepadAxis::RightStickX, AxisDirection::Positive)],
+            Action::Attack => vec![
+                Mouse(MouseButton::Left),
+                Gamepad(GamepadButton::RightTrigger),
+            ],
+            Action::Block => vec![
+                Mouse(MouseButton::Right),
+                Gamepad(GamepadButton::LeftTrigger),
+            ],
+            Action::Throw => vec![Key(KeyCode::KeyF), Gamepad(GamepadButton::RightTrigger2)],
+            Action::Dodge => vec![Key(KeyCode::KeyC), Gamepad(GamepadButton::East)],
+            Action::QuickHeal => vec![Key(KeyCode::KeyQ), Gamepad(GamepadButton::North)],
+            Action::Interact => vec![Key(KeyCode::KeyE), Gamepad(GamepadButton::West)],
+            Action::DebugDamage => vec![Key(KeyCode::KeyK), Gamepad(GamepadButton::LeftTrigger2)],
+            Action::DebugHeal => vec![Key(KeyCode::KeyH), Gamepad(GamepadButton::RightThumb)],
+            // Escape is left to Back, which can't share a binding with Pause.
+            Action::Pause => vec![Key(KeyCode::KeyP), Gamepad(GamepadButton::Start)],
+            Action::NavigateUp => vec![Key(KeyCode::ArrowUp), Gamepad(GamepadButton::DPadUp)],
+            Action::NavigateDown => {
+                vec![Key(KeyCode::ArrowDown), Gamepad(GamepadButton::DPadDown)]
+            }
+            Action::NavigateLeft => {
+                vec![Key(KeyCode::ArrowLeft), Gamepad(GamepadButton::DPadLeft)]
+            }
+            Action::NavigateRight => {
+                vec![Key(KeyCode::ArrowRight), Gamepad(GamepadButton::DPadRight)]
+            }
+            // This is the "A" button on an Xbox controller,
+            // and is conventionally used as the "Select" / "Interact" button in many games
+            Action::Select => vec![Key(KeyCode::Enter), Gamepad(GamepadButton::South)],
+            Action::Back => vec![Key(KeyCode::Escape), Gamepad(GamepadButton::East)],
+        }
+    }
+}
+
+/// Actions in different groups are used in different situations, so they may share
+/// bindings, e.g. the arrow keys both move the player and navigate menus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum ActionGroup {
+    Gameplay,
+    Menu,
+    /// Used both in gameplay and in menus, e.g. pausing, which also closes menus.
+    Global,
+}
+
+impl ActionGroup {
+    /// Whether actions in the two groups can be used at the same time, so they can't share
+    /// a binding.
+    pub fn overlaps(&self, other: ActionGroup) -> bool {
+        *self == other || *self == ActionGroup::Global || other == ActionGroup::Global
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum AxisDirection {
+    Positive,
+    Negative,
+}
+
+/// A physical input that can trigger an [`Action`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+    /// Pushing a gamepad axis one way, e.g. the left stick up.
+    Axis(GamepadAxis, AxisDirection),
+}
+
+impl Binding {
+    pub fn device(&self) -> InputDevice {
+        match self {
+            Binding::Key(_) | Binding::Mouse(_) => InputDevice::KeyboardMouse,
+            Binding::Gamepad(_) | Binding::Axis(..) => InputDevice::Gamepad,
+        }
+    }
+
+    /// How far the input is pressed, from 0 to 1. Buttons and keys are either 0 or 1.
+    fn value(
+        &self,
+        keys: Option<&ButtonInput<KeyCode>>,
+        mouse: Option<&ButtonInput<MouseButton>>,
+        gamepad: Option<&Gamepad>,
+        sticks: &ProcessedSticks,
+    ) -> f32 {
+        let pressed = match self {
+            Binding::Key(key) => keys.is_some_and(|keys| keys.pressed(*key)),
+            Binding::Mouse(button) => mouse.is_some_and(|mouse| mouse.pressed(*button)),
+            Binding::Gamepad(button) => gamepad.is_some_and(|gamepad| gamepad.pressed(*button)),
+            Binding::Axis(axis, direction) => {
+                let value = sticks.axis(*axis).unwrap_or_else(|| {
+                    gamepad
+                        .and_then(|gamepad| gamepad.get(*axis))
+                        .filter(|value| value.abs() > AXIS_THRESHOLD)
+                        .unwrap_or(0.0)
+                });
+                let value = match direction {
+                    AxisDirection::Positive => value,
+                    AxisDirection::Negative => -value,
+                };
+
+                return value.max(0.0);
+            }
+        };
+
+        if pressed { 1.0 } else { 0.0 }
+    }
+
+    /// Whether the input was pressed this frame. Axes never are, as they aren't pressed
+    /// so much as pushed.
+    fn just_pressed(
+        &self,
+        keys: Option<&ButtonInput<KeyCode>>,
+        mouse: Option<&ButtonInput<MouseButton>>,
+        gamepad: Option<&Gamepad>,
+    ) -> bool {
+        match self {
+            Binding::Key(key) => keys.is_some_and(|keys| keys.just_pressed(*key)),
+            Binding::Mouse(button) => mouse.is_some_and(|mouse| mouse.just_pressed(*button)),
+            Binding::Gamepad(button) => {
+                gamepad.is_some_and(|gamepad| gamepad.just_pressed(*button))
+            }
+            Binding::Axis(..) => false,
+        }
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Binding::Key(key) => {
+                let name = format!("{key:?}");
+                let name = name
+                    .strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                    .or_else(|| name.strip_prefix("Arrow"))
+                    .unwrap_or(&name);
+                f.write_str(name)
+            }
+            Binding::Mouse(MouseButton::Left) => f.write_str("LMB"),
+            Binding::Mouse(MouseButton::Right) => f.write_str("RMB"),
+            Binding::Mouse(MouseButton::Middle) => f.write_str("MMB"),
+            Binding::Mouse(button) => write!(f, "Mouse {button:?}"),
+            // Labelled like an Xbox controller.
+            Binding::Gamepad(button) => f.write_str(match button {
+                GamepadButton::South => "A",
+                GamepadButton::East => "B",
+                GamepadButton::West => "X",
+                GamepadButton::North => "Y",
+                GamepadButton::LeftTrigger => "LB",
+                GamepadButton::LeftTrigger2 => "LT",
+                GamepadButton::RightTrigger => "RB",
+                GamepadButton::RightTrigger2 => "RT",
+                GamepadButton::LeftThumb => "LS",
+                GamepadButton::RightThumb => "RS",
+                GamepadButton::Start => "Start",
+                GamepadButton::Select => "Back",
+                GamepadButton::DPadUp => "D-Pad Up",
+                GamepadButton::DPadDown => "D-Pad Down",
+                GamepadButton::DPadLeft => "D-Pad Left",
+                GamepadButton::DPadRight => "D-Pad Right",
+                _ => return write!(f, "{button:?}"),
+            }),
+            Binding::Axis(axis, direction) => {
+                let stick = match axis {
+                    GamepadAxis::LeftStickX | GamepadAxis::LeftStickY => "Left Stick",
+                    GamepadAxis::RightStickX | GamepadAxis::RightStickY => "Right Stick",
+                    _ => return write!(f, "{axis:?} {direction:?}"),
+                };
+                let direction = match (axis, direction) {
+                    (
+                        GamepadAxis::LeftStickY | GamepadAxis::RightStickY,
+                        AxisDirection::Positive,
+                    ) => "Up",
+                    (
+                        GamepadAxis::LeftStickY | GamepadAxis::RightStickY,
+                        AxisDirection::Negative,
+                    ) => "Down",
+                    (_, AxisDirection::Positive) => "Right",
+                    (_, AxisDirection::Negative) => "Left",
+                };
+                write!(f, "{stick} {direction}")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum Stick {
+    Left,
+    Right,
+}
+
+impl Stick {
+    pub const ALL: [Stick; 2] = [Stick::Left, Stick::Right];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stick::Left => "Left stick",
+            Stick::Right => "Right stick",
+        }
+    }
+}
+
+/// How a stick's position turns into how far its actions are pressed. The deadzones are
+/// radial, so they don't favour the diagonals or snap to the axes.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct StickSettings {
+    /// Closer to the center than this, the stick counts as released, which hides drift.
+    pub inner_deadzone: f32,
+    /// Further from the center than this, the stick counts as pushed all the way, as worn
+    /// sticks don't always reach the edge.
+    pub outer_deadzone: f32,
+    pub curve: ResponseCurve,
+    /// Scales the stick after the curve, so it's pushed all the way sooner.
+    pub sensitivity: f32,
+}
+
+impl Default for StickSettings {
+    fn default() -> Self {
+        Self {
+            inner_deadzone: 0.15,
+            outer_deadzone: 0.95,
+            curve: ResponseCurve::Linear,
+            sensitivity: 1.0,
+        }
+    }
+}
+
+impl StickSettings {
+    /// Turns a raw stick position into one that is never longer than 1, pointing the same
+    /// way.
+    pub fn process(&self, position: Vec2) -> Vec2 {
+        let distance = position.length();
+        if distance <= self.inner_deadzone {
+            return Vec2::ZERO;
+        }
+
+        let range = (self.outer_deadzone - self.inner_deadzone).max(f32::EPSILON);
+        let pushed = ((distance - self.inner_deadzone) / range).min(1.0);
+
+        position / distance * (self.curve.apply(pushed) * self.sensitivity).min(1.0)
+    }
+}
+
+/// How far a stick's actions are pressed as it is pushed from the inner to the outer
+/// deadzone. Steeper curves give finer control close to the center.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect, Serialize, Deserialize)]
+pub enum ResponseCurve {
+    #[default]
+    Linear,
+    Quadratic,
+    Cubic,
+}
+
+impl ResponseCurve {
+    pub const ALL: [ResponseCurve; 3] = [
+        ResponseCurve::Linear,
+        ResponseCurve::Quadratic,
+        ResponseCurve::Cubic,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResponseCurve::Linear => "Linear",
+            ResponseCurve::Quadratic => "Quadratic",
+            ResponseCurve::Cubic => "Cubic",
+        }
+    }
+
+    fn apply(&self, pushed: f32) -> f32 {
+        match self {
+            ResponseCurve::Linear => pushed,
+            ResponseCurve::Quadratic => pushed.powi(2),
+            ResponseCurve::Cubic => pushed.powi(3),
+        }
+    }
+}
+
+/// The stick positions of this frame, after their [`StickSettings`].
+#[derive(Debug, Clone, Copy, Default)]
+struct ProcessedSticks {
+    left: Vec2,
+    right: Vec2,
+}
+
+impl ProcessedSticks {
+    fn axis(&self, axis: GamepadAxis) -> Option<f32> {
+        match axis {
+            GamepadAxis::LeftStickX => Some(self.left.x),
+            GamepadAxis::LeftStickY => Some(self.left.y),
+            GamepadAxis::RightStickX => Some(self.right.x),
+            GamepadAxis::RightStickY => Some(self.right.y),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum InputDevice {
+    #[default]
+    KeyboardMouse,
+    Gamepad,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum InputMapError {
+    #[error("could not access the input config: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not read the input config: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("could not write the input config: {0}")]
+    Write(#[from] ron::Error),
+}
+
+/// Which [`Binding`]s trigger each [`Action`], and how the sticks are processed. Changing it
+/// rebinds the actions right away.
+#[derive(Resource, Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct InputMap {
+    bindings: BTreeMap<Action, Vec<Binding>>,
+    left_stick: StickSettings,
+    right_stick: StickSettings,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self {
+            bindings: Action::ALL
+                .iter()
+                .map(|action| (*action, action.default_bindings()))
+                .collect(),
+            left_stick: default(),
+            right_stick: default(),
+        }
+    }
+}
+
+impl InputMap {
+    pub fn stick(&self, stick: Stick) -> &StickSettings {
+        match stick {
+            Stick::Left => &self.left_stick,
+            Stick::Right => &self.right_stick,
+        }
+    }
+
+    pub fn stick_mut(&mut self, stick: Stick) -> &mut StickSettings {
+        match stick {
+            Stick::Left => &mut self.left_stick,
+            Stick::Right => &mut self.right_stick,
+        }
+    }
+
+    pub fn bindings(&self, action: Action) -> &[Binding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// The first binding of the action on the device, e.g. to show in a button prompt.
+    pub fn prompt(&self, action: Action, device: InputDevice) -> Option<Binding> {
+        self.bindings(action)
+            .iter()
+            .find(|binding| binding.device() == device)
+            .copied()
+    }
+
+    /// Whether any of the action's bindings on the given devices was pressed this frame,
+    /// e.g. to tell which gamepad a player joins with.
+    pub fn just_pressed(
+        &self,
+        action: Action,
+        keys: Option<&ButtonInput<KeyCode>>,
+        mouse: Option<&ButtonInput<MouseButton>>,
+        gamepad: Option<&Gamepad>,
+    ) -> bool {
+        self.bindings(action)
+            .iter()
+            .any(|binding| binding.just_pressed(keys, mouse, gamepad))
+    }
+
+    /// Binds the action to `binding` in place of `replacing`, keeping its other bindings,
+    /// or adds it if there is nothing to replace. Other actions in overlapping groups which
+    /// were already bound to it get the replaced binding instead and are returned.
+    pub fn rebind(
+        &mut self,
+        action: Action,
+        replacing: Option<Binding>,
+        binding: Binding,
+    ) -> Vec<Action> {
+        let replaced = replacing.filter(|replacing| self.bindings(action).contains(replacing));
+
+        let mut conflicts = Vec::new();
+
+        for (other, bindings) in &mut self.bindings {
+            if *other == action
+                || !other.group().overlaps(action.group())
+                || !bindings.contains(&binding)
+            {
+                continue;
+            }
+
+            bindings.retain(|other_binding| *other_binding != binding);
+            if let Some(replaced) = replaced
+                && !bindings.contains(&replaced)
+            {
+                bindings.push(replaced);
+            }
+            conflicts.push(*other);
+        }
+
+        let bindings = self.bindings.entry(action).or_default();
+        match replaced.and_then(|replaced| bindings.iter().position(|old| *old == replaced)) {
+            Some(slot) => bindings[slot] = binding,
+            None => bindings.push(binding),
+        }
+
+        // The action may have been bound to it in another slot already.
+        let mut seen = false;
+        bindings.retain(|old_binding| {
+            let duplicate = seen && *old_binding == binding;
+            seen |= *old_binding == binding;
+            !duplicate
+        });
+
+        conflicts
+    }
+
+    /// Restores the default bindings. The stick settings are kept.
+    pub fn reset(&mut self) {
+        self.bindings = Self::default().bindings;
+    }
+
+    /// Reads the saved input map, or falls back to the defaults. Actions missing from the
+    /// file, e.g. because they were added after it was saved, keep their defaults.
+    fn load() -> Self {
+        Self::or_default(Self::read())
+    }
+
+    fn or_default(saved: Result<Option<Self>, InputMapError>) -> Self {
+        let mut input_map = Self::default();
+
+        match saved {
+            Ok(Some(saved)) => {
+                input_map.bindings.extend(saved.bindings);
+                input_map.left_stick = saved.left_stick;
+                input_map.right_stick = saved.right_stick;
+            }
+            Ok(None) => {}
+            Err(error) => warn!("Using the default input map: {error}"),
+        }
+
+        input_map
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn read() -> Result<Option<Self>, InputMapError> {
+        match std::fs::read_to_string(INPUT_MAP_PATH) {
+            Ok(text) => Ok(Some(ron::from_str(&text)?)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn save(&self) -> Result<(), InputMapError> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+
+        if let Some(directory) = std::path::Path::new(INPUT_MAP_PATH).parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+
+        Ok(std::fs::write(INPUT_MAP_PATH, text)?)
+    }
+
+    // There is no file system on the web, so bindings only last until the page is closed.
+    #[cfg(target_family = "wasm")]
+    fn read() -> Result<Option<Self>, InputMapError> {
+        Ok(None)
+    }
+
+    #[cfg(target_family = "wasm")]
+    fn save(&self) -> Result<(), InputMapError> {
+        Ok(())
+    }
+}
+
+/// Waits for the next input on the device and binds the action to it. No action counts
+/// as pressed in the meantime. Removed again once done, which triggers a
+/// [`RebindFinishedEvent`]. Escape or the Back button on any gamepad cancels it.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rebinding {
+    pub action: Action,
+    pub device: InputDevice,
+    /// The binding to replace, or nothing to add a new one.
+    pub replacing: Option<Binding>,
+}
+
+/// Triggered when a [`Rebinding`] is done.
+#[derive(Event, Debug, Clone)]
+pub struct RebindFinishedEvent {
+    pub action: Action,
+    /// What the action was bound to, or nothing if the rebinding was cancelled.
+    pub binding: Option<Binding>,
+    /// The actions that had to give up the binding.
+    pub conflicts: Vec<Action>,
+}
+
+/// The devices whose input goes to an entity's own [`ActionState`], e.g. a player's in
+/// local co-op.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Component)]
+#[require(ActionState)]
+pub enum InputSource {
+    /// The keyboard, mouse and active gamepad, for playing alone.
+    All,
+    KeyboardMouse,
+    Gamepad(Entity),
+}
+
+/// How far each [`Action`] is pressed this frame and the last. As a component, it only
+/// reads the devices of the entity's [`InputSource`].
+#[derive(Resource, Component, Debug, Clone, Default)]
+pub struct ActionState {
+    current: HashMap<Action, f32>,
+    previous: HashMap<Action, f32>,
+    /// Set while rebinding, so the input being bound doesn't do anything else.
+    suppressed: bool,
+    /// The device that most recently pressed any bound input.
+    pub last_device: InputDevice,
+}
+
+impl ActionState {
+    /// How far the action is pressed, from 0 to 1.
+    pub fn value(&self, action: Action) -> f32 {
+        if self.suppressed {
+            return 0.0;
+        }
+
+        self.current.get(&action).copied().unwrap_or(0.0)
+    }
+
+    pub fn pressed(&self, action: Action) -> bool {
+        self.value(action) > 0.0
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.pressed(action) && self.previous.get(&action).is_none_or(|value| *value <= 0.0)
+    }
+
+    /// Combines four actions into a direction, e.g. movement from the four move actions.
+    /// Y points up. Never longer than 1, so diagonals aren't faster.
+    pub fn axis_pair(&self, up: Action, down: Action, left: Action, right: Action) -> Vec2 {
+        Vec2::new(
+            self.value(right) - self.value(left),
+            self.value(up) - self.value(down),
+        )
+        .clamp_length_max(1.0)
+    }
+
+    /// Moves on to the next frame, reading every action from the given devices.
+    fn update(
+        &mut self,
+        input_map: &InputMap,
+        keys: Option<&ButtonInput<KeyCode>>,
+        mouse: Option<&ButtonInput<MouseButton>>,
+        gamepad: Option<&Gamepad>,
+        suppressed: bool,
+    ) {
+        self.suppressed = suppressed;
+
+        std::mem::swap(&mut self.previous, &mut self.current);
+        self.current.clear();
+
+        let sticks = gamepad.map_or_else(ProcessedSticks::default, |gamepad| ProcessedSticks {
+            left: input_map.left_stick.process(gamepad.left_stick()),
+            right: input_map.right_stick.process(gamepad.right_stick()),
+        });
+
+        for (action, bindings) in &input_map.bindings {
+            let mut value: f32 = 0.0;
+
+            for binding in bindings {
+                let binding_value = binding.value(keys, mouse, gamepad, &sticks);
+                if binding_value > 0.0 {
+                    self.last_device = binding.device();
+                }
+                value = value.max(binding_value);
+            }
+
+            self.current.insert(*action, value);
+        }
+    }
+}
+
+/// A run condition for systems that should run when the action was just pressed.
+pub fn action_just_pressed(action: Action) -> impl Fn(Res<ActionState>) -> bool + Clone {
+    move |action_state: Res<ActionState>| action_state.just_pressed(action)
+}
+
+fn update_action_state(
+    mut input_map: ResMut<InputMap>,
+    rebinding: Option<Res<Rebinding>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepad_res: Option<Res<GamepadRes>>,
+    gamepads: Query<&Gamepad>,
+    mut action_state: ResMut<ActionState>,
+    mut source_query: Query<(&InputSource, &mut ActionState)>,
+    mut commands: Commands,
+) {
+    let gamepad = gamepad_res.and_then(|gamepad_res| gamepads.get(gamepad_res.0).ok());
+
+    // Actions stay suppressed for the frame the rebinding finishes, as the input that
+    // finished it is still just pressed.
+    let suppressed = rebinding.is_some();
+
+    if let Some(rebinding) = rebinding {
+        // Checked first, so a player on any device can back out of rebinding another.
+        let cancelled = keys.just_pressed(CANCEL_REBIND_KEY)
+            || gamepads
+                .iter()
+                .any(|gamepad| gamepad.just_pressed(CANCEL_REBIND_BUTTON));
+
+        if cancelled {
+            commands.remove_resource::<Rebinding>();
+            commands.trigger(RebindFinishedEvent {
+                action: rebinding.action,
+                binding: None,
+                conflicts: Vec::new(),
+            });
+        } else if let Some(binding) = captured_binding(rebinding.device, &keys, &mouse, gamepad) {
+            let conflicts = input_map.rebind(rebinding.action, rebinding.replacing, binding);
+            commands.remove_resource::<Rebinding>();
+            commands.trigger(RebindFinishedEvent {
+                action: rebinding.action,
+                binding: Some(binding),
+                conflicts,
+            });
+        }
+    }
+
+    action_state.update(&input_map, Some(&keys), Some(&mouse), gamepad, suppressed);
+
+    for (source, mut source_state) in &mut source_query {
+        let (keyboard_mouse, gamepad) = match source {
+            InputSource::All => (true, gamepad),
+            InputSource::KeyboardMouse => (true, None),
+            InputSource::Gamepad(entity) => (false, gamepads.get(*entity).ok()),
+        };
+
+        source_state.update(
+            &input_map,
+            keyboard_mouse.then_some(&*keys),
+            keyboard_mouse.then_some(&*mouse),
+            gamepad,
+            suppressed,
+        );
+    }
+}
+
+/// The first input just pressed on the device. The cancel inputs have been checked for
+/// already.
+fn captured_binding(
+    device: InputDevice,
+    keys: &ButtonInput<KeyCode>,
+    mouse: &ButtonInput<MouseButton>,
+    gamepad: Option<&Gamepad>,
+) -> Option<Binding> {
+    match device {
+        InputDevice::KeyboardMouse => keys
+            .get_just_pressed()
+            .next()
+            .map(|key| Binding::Key(*key))
+            .or_else(|| {
+                mouse
+                    .get_just_pressed()
+                    .next()
+                    .map(|button| Binding::Mouse(*button))
+            }),
+        InputDevice::Gamepad => {
+            let gamepad = gamepad?;
+
+            gamepad
+                .get_just_pressed()
+                .next()
+                .map(|button| Binding::Gamepad(*button))
+                .or_else(|| {
+                    STICK_AXES.iter().find_map(|axis| {
+                        let value = gamepad.get(*axis)?;
+                        let direction = if value > 0.0 {
+                            AxisDirection::Positive
+                        } else {
+                            AxisDirection::Negative
+                        };
+
+                        (value.abs() > REBIND_AXIS_THRESHOLD)
+                            .then_some(Binding::Axis(*axis, direction))
+                    })
+                })
+        }
+    }
+}
+
+/// Bevy applies a deadzone to each axis on its own, which would bias the sticks towards the
+/// axes before their [`StickSettings`] get to them.
+fn disable_axial_deadzones(mut gamepads: Query<&mut GamepadSettings, Added<GamepadSettings>>) {
+    let Ok(axis_settings) = AxisSettings::new(-1.0, 0.0, 0.0, 1.0, 0.01) else {
+        return;
+    };
+
+    for mut settings in &mut gamepads {
+        for axis in STICK_AXES {
+            settings.axis_settings.insert(axis, axis_settings.clone());
+        }
+    }
+}
+
+fn save_input_map(input_map: Res<InputMap>) {
+    if let Err(error) = input_map.save() {
+        warn!("Failed to save the input map: {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved(text: &str) -> Result<Option<InputMap>, InputMapError> {
+        Ok(Some(ron::from_str(text)?))
+    }
+
+    #[test]
+    fn rebind_takes_the_binding_from_every_overlapping_action() {
+        let mut input_map = InputMap::default();
+        let binding = Binding::Key(KeyCode::KeyG);
+        // Gameplay and menu actions may share a binding, but pausing overlaps both.
+        for action in [Action::Jump, Action::Select] {
+            input_map.bindings.entry(action).or_default().push(binding);
+        }
+
+        let conflicts = input_map.rebind(Action::Pause, Some(Binding::Key(KeyCode::KeyP)), binding);
+
+        assert_eq!(conflicts, [Action::Jump, Action::Select]);
+        for action in conflicts {
+            assert!(!input_map.bindings(action).contains(&binding));
+            assert!(
+                input_map
+                    .bindings(action)
+                    .contains(&Binding::Key(KeyCode::KeyP))
+            );
+        }
+        assert_eq!(
+            input_map.bindings(Action::Pause),
+            [binding, Binding::Gamepad(GamepadButton::Start)]
+        );
+    }
+
+    #[test]
+    fn rebind_keeps_bindings_of_other_groups() {
+        let mut input_map = InputMap::default();
+        let binding = Binding::Key(KeyCode::KeyW);
+
+        let conflicts = input_map.rebind(Action::NavigateUp, None, binding);
+
+        assert!(conflicts.is_empty());
+        assert!(input_map.bindings(Action::MoveUp).contains(&binding));
+        assert!(input_map.bindings(Action::NavigateUp).contains(&binding));
+    }
+
+    #[test]
+    fn default_bindings_do_not_conflict() {
+        let input_map = InputMap::default();
+
+        for (action, bindings) in &input_map.bindings {
+            for (other, other_bindings) in &input_map.bindings {
+                if action != other && action.group().overlaps(other.group()) {
+                    assert!(
+                        !bindings
+                            .iter()
+                            .any(|binding| other_bindings.contains(binding)),
+                        "{action:?} and {other:?} share a binding"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn load_falls_back_to_the_defaults() {
+        assert_eq!(InputMap::or_default(Ok(None)), InputMap::default());
+        assert_eq!(
+            InputMap::or_default(saved("not an input map")),
+            InputMap::default()
+        );
+        assert_eq!(
+            InputMap::or_default(Err(std::io::Error::other("no access").into())),
+            InputMap::default()
+        );
+    }
+
+    #[test]
+    fn load_keeps_defaults_for_missing_actions() {
+        let input_map = InputMap::or_default(saved("(bindings: {Jump: [Key(KeyJ)]})"));
+
+        assert_eq!(
+            input_map.bindings(Action::Jump),
+            [Binding::Key(KeyCode::KeyJ)]
+        );
+        assert_eq!(
+            input_map.bindings(Action::Dodge),
+            InputMap::default().bindings(Action::Dodge)
+        );
+    }
+
+    fn settings(curve: ResponseCurve) -> StickSettings {
+        StickSettings {
+            inner_deadzone: 0.2,
+            outer_deadzone: 0.8,
+            curve,
+            sensitivity: 1.0,
+        }
+    }
+
+    #[test]
+    fn inner_deadzone_is_radial() {
+        let settings = settings(ResponseCurve::Linear);
+
+        assert_eq!(settings.process(Vec2::new(0.1, 0.1)), Vec2::ZERO);
+        assert_eq!(settings.process(Vec2::new(0.0, -0.2)), Vec2::ZERO);
+        assert_ne!(settings.process(Vec2::new(0.21, 0.0)), Vec2::ZERO);
+    }
+
+    #[test]
+    fn output_never_exceeds_one() {
+        let mut settings = settings(ResponseCurve::Linear);
+        settings.sensitivity = 2.0;
+
+        for position in [Vec2::new(1.0, 1.0), Vec2::X, Vec2::new(0.6, -0.7)] {
+            let processed = settings.process(position);
+            assert!(processed.length() <= 1.0 + 1e-6);
+            assert!(
+                processed
+                    .normalize()
+                    .abs_diff_eq(position.normalize(), 1e-6)
+            );
+        }
+
+        assert!((settings.process(Vec2::new(0.0, 0.9)).length() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn output_starts_from_zero_at_the_deadzone_edge() {
+        let settings = settings(ResponseCurve::Linear);
+
+        let just_outside = settings.process(Vec2::new(0.2 + 1e-4, 0.0));
+        assert!(just_outside.length() < 1e-3);
+
+        // Halfway between the deadzones is pushed halfway.
+        assert!((settings.process(Vec2::new(0.5, 0.0)).x - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn response_curves_give_finer_control_near_the_center() {
+        let halfway = Vec2::new(0.0, 0.5);
+
+        let linear = settings(ResponseCurve::Linear).process(halfway).y;
+        let quadratic = settings(ResponseCurve::Quadratic).process(halfway).y;
+        let cubic = settings(ResponseCurve::Cubic).process(halfway).y;
+
+        assert!((linear - 0.5).abs() < 1e-6);
+        assert!((quadratic - 0.25).abs() < 1e-6);
+        assert!((cubic - 0.125).abs() < 1e-6);
+    }
+}
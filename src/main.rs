@@ -9,6 +9,7 @@ mod audio;
 mod dev_tools;
 mod game;
 mod gamepad;
+mod input;
 mod menus;
 mod screens;
 mod theme;
@@ -53,6 +54,7 @@ impl Plugin for AppPlugin {
             #[cfg(feature = "dev")]
             dev_tools::plugin,
             gamepad::plugin,
+            input::plugin,
             menus::plugin,
             screens::plugin,
             theme::plugin,
@@ -0,0 +1,366 @@
+//! The controls page, listing the keyboard and gamepad bindings of every action. Selecting
+//! a binding waits for the next key or button press and puts it in place of the action's
+//! first binding on that device, keeping the others. Opened from the settings menu.
+
+use bevy::input::mouse::{AccumulatedMouseScroll, MouseScrollUnit};
+use bevy::input_focus::InputFocus;
+use bevy::input_focus::directional_navigation::DirectionalNavigationMap;
+use bevy::math::CompassOctant;
+use bevy::prelude::*;
+
+use crate::input::{
+    Action, InputDevice, InputMap, RebindFinishedEvent, Rebinding, action_just_pressed,
+};
+use crate::menus::Menu;
+use crate::theme::palette::LABEL_TEXT;
+use crate::theme::widget::{self, ButtonAssets};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Controls), spawn_controls_menu);
+    app.add_systems(OnExit(Menu::Controls), cancel_rebinding);
+    app.add_systems(
+        Update,
+        (
+            go_back.run_if(action_just_pressed(Action::Back)),
+            update_binding_buttons.run_if(
+                resource_changed::<InputMap>
+                    .or(resource_exists_and_changed::<Rebinding>)
+                    .or(resource_removed::<Rebinding>),
+            ),
+            scroll_to_focus.run_if(resource_changed::<InputFocus>),
+            scroll_with_mouse_wheel,
+        )
+            .run_if(in_state(Menu::Controls)),
+    );
+
+    app.add_observer(on_rebind_finished);
+}
+
+const ACTION_COLUMN_WIDTH: f32 = 220.0;
+/// Matches the width of [`widget::button_compact`].
+const BINDING_COLUMN_WIDTH: f32 = 280.0;
+/// How far one line of mouse wheel scrolling moves the list, in logical pixels.
+const SCROLL_LINE_HEIGHT: f32 = 48.0;
+
+/// The scrolling list of bindings.
+#[derive(Component, Debug)]
+struct ControlsList;
+
+/// The text telling the player what is going on, e.g. which action is being rebound.
+#[derive(Component, Debug)]
+struct ControlsStatus;
+
+/// A button showing the bindings of an action on a device, which rebinds it when selected.
+#[derive(Component, Debug, Clone, Copy)]
+struct BindingButton {
+    action: Action,
+    device: InputDevice,
+}
+
+fn spawn_controls_menu(
+    input_map: Res<InputMap>,
+    button_assets: Res<ButtonAssets>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut directional_nav_map: ResMut<DirectionalNavigationMap>,
+    mut input_focus: ResMut<InputFocus>,
+    mut commands: Commands,
+) {
+    let ui_root = commands
+        .spawn((
+            widget::ui_root("Controls Menu"),
+            GlobalZIndex(2),
+            DespawnOnExit(Menu::Controls),
+            children![widget::header("Controls")],
+        ))
+        .id();
+
+    let list = commands
+        .spawn((
+            Name::new("Controls List"),
+            ControlsList,
+            Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: px(6),
+                height: percent(55),
+                overflow: Overflow::scroll_y(),
+                ..default()
+            },
+            children![(
+                controls_row(),
+                children![
+                    row_text("Action", ACTION_COLUMN_WIDTH),
+                    row_text("Keyboard & mouse", BINDING_COLUMN_WIDTH),
+                    row_text("Gamepad", BINDING_COLUMN_WIDTH),
+                ],
+            )],
+        ))
+        .id();
+    commands.entity(ui_root).add_child(list);
+
+    let mut keyboard_buttons = Vec::new();
+    let mut gamepad_buttons = Vec::new();
+
+    for action in Action::ALL {
+        let [keyboard_button, gamepad_button] = [InputDevice::KeyboardMouse, InputDevice::Gamepad]
+            .map(|device| {
+                commands
+                    .spawn((
+                        widget::button_compact(
+                            &button_assets,
+                            &mut texture_atlas_layouts,
+                            binding_text(&input_map, action, device),
+                            move |_: On<Pointer<Click>>,
+                                  input_map: Res<InputMap>,
+                                  mut commands: Commands| {
+                                commands.insert_resource(Rebinding {
+                                    action,
+                                    device,
+                                    replacing: input_map.prompt(action, device),
+                                });
+                            },
+                        ),
+                        BindingButton { action, device },
+                    ))
+                    .id()
+            });
+
+        let row = commands
+            .spawn((
+                controls_row(),
+                children![row_text(action.name(), ACTION_COLUMN_WIDTH)],
+            ))
+            .add_children(&[keyboard_button, gamepad_button])
+            .id();
+        commands.entity(list).add_child(row);
+
+        keyboard_buttons.push(keyboard_button);
+        gamepad_buttons.push(gamepad_button);
+    }
+
+    commands.entity(ui_root).with_child((
+        Name::new("Controls Status"),
+        ControlsStatus,
+        Text::new("Select a binding to change it"),
+        TextFont::from_font_size(20.0),
+        TextColor(LABEL_TEXT),
+    ));
+
+    let reset_button = commands
+        .spawn(widget::button(
+            &button_assets,
+            &mut texture_atlas_layouts,
+            "Reset all",
+            reset_bindings,
+        ))
+        .id();
+    let back_button = commands
+        .spawn(widget::button(
+            &button_assets,
+            &mut texture_atlas_layouts,
+            "Back",
+            go_back_on_click,
+        ))
+        .id();
+    let button_row = commands
+        .spawn((
+            Name::new("Controls Buttons"),
+            Node {
+                column_gap: px(20),
+                ..default()
+            },
+        ))
+        .add_children(&[reset_button, back_button])
+        .id();
+    commands.entity(ui_root).add_child(button_row);
+
+    // One column per device, each ending in the button below it.
+    directional_nav_map.add_looping_edges(
+        &[keyboard_buttons.as_slice(), &[reset_button]].concat(),
+        CompassOctant::South,
+    );
+    directional_nav_map.add_looping_edges(
+        &[gamepad_buttons.as_slice(), &[back_button]].concat(),
+        CompassOctant::South,
+    );
+    for (keyboard_button, gamepad_button) in keyboard_buttons.iter().zip(&gamepad_buttons) {
+        directional_nav_map.add_symmetrical_edge(
+            *keyboard_button,
+            *gamepad_button,
+            CompassOctant::East,
+        );
+    }
+    directional_nav_map.add_symmetrical_edge(reset_button, back_button, CompassOctant::East);
+
+    input_focus.0 = keyboard_buttons.first().copied();
+}
+
+/// A row of the list: an action's name, then its binding button for each device.
+fn controls_row() -> impl Bundle {
+    (
+        Name::new("Controls Row"),
+        Node {
+            align_items: AlignItems::Center,
+            column_gap: px(20),
+            flex_shrink: 0.0,
+            ..default()
+        },
+    )
+}
+
+fn row_text(text: impl Into<String>, width: f32) -> impl Bundle {
+    (
+        Text(text.into()),
+        TextFont::from_font_size(24.0),
+        TextColor(LABEL_TEXT),
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            width: px(width),
+            ..default()
+        },
+    )
+}
+
+/// All bindings of the action on the device, e.g. "W / Up".
+fn binding_text(input_map: &InputMap, action: Action, device: InputDevice) -> String {
+    let bindings = input_map
+        .bindings(action)
+        .iter()
+        .filter(|binding| binding.device() == device)
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+
+    if bindings.is_empty() {
+        "-".to_string()
+    } else {
+        bindings.join(" / ")
+    }
+}
+
+fn update_binding_buttons(
+    input_map: Res<InputMap>,
+    rebinding: Option<Res<Rebinding>>,
+    buttons: Query<(Entity, &BindingButton)>,
+    children: Query<&Children>,
+    mut texts: Query<&mut Text, Without<ControlsStatus>>,
+    mut status: Single<&mut Text, With<ControlsStatus>>,
+) {
+    for (entity, button) in &buttons {
+        let waiting = rebinding.as_deref().is_some_and(|rebinding| {
+            rebinding.action == button.action && rebinding.device == button.device
+        });
+        let text = if waiting {
+            "Press...".to_string()
+        } else {
+            binding_text(&input_map, button.action, button.device)
+        };
+
+        for descendant in children.iter_descendants(entity) {
+            if let Ok(mut button_text) = texts.get_mut(descendant) {
+                button_text.0.clone_from(&text);
+            }
+        }
+    }
+
+    if let Some(rebinding) = rebinding
+        && rebinding.is_changed()
+    {
+        let input = match rebinding.device {
+            InputDevice::KeyboardMouse => "a key or mouse button",
+            InputDevice::Gamepad => "a button or push a stick",
+        };
+        status.0 = format!(
+            "Press {input} for {}, or Escape or Back to cancel",
+            rebinding.action.name()
+        );
+    }
+}
+
+fn on_rebind_finished(
+    finished: On<RebindFinishedEvent>,
+    mut status: Query<&mut Text, With<ControlsStatus>>,
+) {
+    let action = finished.action.name();
+    let message = match (finished.binding, finished.conflicts.as_slice()) {
+        (None, _) => format!("Kept the bindings of {action}"),
+        (Some(binding), []) => format!("{action} is now bound to {binding}"),
+        (Some(binding), conflicts) => format!(
+            "{action} is now bound to {binding}, which {} used before",
+            conflicts
+                .iter()
+                .map(Action::name)
+                .collect::<Vec<_>>()
+                .join(" and ")
+        ),
+    };
+
+    for mut status in &mut status {
+        status.0.clone_from(&message);
+    }
+}
+
+fn reset_bindings(
+    _: On<Pointer<Click>>,
+    mut input_map: ResMut<InputMap>,
+    mut status: Single<&mut Text, With<ControlsStatus>>,
+) {
+    input_map.reset();
+    status.0 = "Reset all bindings to their defaults".to_string();
+}
+
+fn cancel_rebinding(mut commands: Commands) {
+    commands.remove_resource::<Rebinding>();
+}
+
+/// Keeps the focused binding button visible while navigating the list with a gamepad or
+/// the keyboard.
+fn scroll_to_focus(
+    input_focus: Res<InputFocus>,
+    buttons: Query<(&ComputedNode, &UiGlobalTransform), With<BindingButton>>,
+    mut list: Single<(&ComputedNode, &UiGlobalTransform, &mut ScrollPosition), With<ControlsList>>,
+) {
+    let Some((button_node, button_transform)) =
+        input_focus.0.and_then(|focused| buttons.get(focused).ok())
+    else {
+        return;
+    };
+    let (list_node, list_transform, scroll_position) = &mut *list;
+
+    // Both are in physical pixels, relative to the top of the visible part of the list.
+    let list_top = list_transform.translation.y - list_node.size.y / 2.0;
+    let button_top = button_transform.translation.y - button_node.size.y / 2.0 - list_top;
+    let button_bottom = button_top + button_node.size.y;
+
+    let overshoot = if button_top < 0.0 {
+        button_top
+    } else if button_bottom > list_node.size.y {
+        button_bottom - list_node.size.y
+    } else {
+        return;
+    };
+
+    scroll_position.y = (scroll_position.y + overshoot * list_node.inverse_scale_factor).max(0.0);
+}
+
+fn scroll_with_mouse_wheel(
+    mouse_scroll: Res<AccumulatedMouseScroll>,
+    mut list: Single<&mut ScrollPosition, With<ControlsList>>,
+) {
+    if mouse_scroll.delta.y == 0.0 {
+        return;
+    }
+
+    let delta = match mouse_scroll.unit {
+        MouseScrollUnit::Line => mouse_scroll.delta.y * SCROLL_LINE_HEIGHT,
+        MouseScrollUnit::Pixel => mouse_scroll.delta.y,
+    };
+    list.y = (list.y - delta).max(0.0);
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
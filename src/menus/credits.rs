@@ -1,18 +1,16 @@
 //! The credits menu.
 
-use crate::gamepad::gamepad_just_pressed;
+use crate::input::{Action, action_just_pressed};
 use crate::theme::widget::ButtonAssets;
 use crate::{asset_tracking::LoadResource, audio::music, menus::Menu, theme::prelude::*};
 use bevy::input_focus::InputFocus;
-use bevy::{ecs::spawn::SpawnIter, input::common_conditions::input_just_pressed, prelude::*};
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Credits), spawn_credits_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Credits).and(
-            input_just_pressed(KeyCode::Escape).or(gamepad_just_pressed(GamepadButton::East)),
-        )),
+        go_back.run_if(in_state(Menu::Credits).and(action_just_pressed(Action::Back))),
     );
 
     app.load_resource::<CreditsAssets>();
@@ -0,0 +1,154 @@
+//! The dialogue menu, showing the current line of a conversation and its choices.
+
+use bevy::input_focus::InputFocus;
+use bevy::input_focus::directional_navigation::DirectionalNavigationMap;
+use bevy::math::CompassOctant;
+use bevy::prelude::*;
+
+use crate::game::dialogue::{
+    ActiveDialogue, AdvanceDialogueEvent, ChooseDialogueEvent, DialogueTree,
+};
+use crate::game::flags::GameFlags;
+use crate::menus::Menu;
+use crate::theme::widget::{self, ButtonAssets};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        spawn_dialogue_panel
+            .run_if(in_state(Menu::Dialogue).and(resource_exists_and_changed::<ActiveDialogue>)),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+struct DialoguePanel;
+
+/// A button of the [`DialoguePanel`], which is taken out of the navigation map again along
+/// with the panel.
+#[derive(Component, Debug, Clone, Copy)]
+struct DialogueButton;
+
+/// Rebuilds the panel whenever the conversation moves on.
+fn spawn_dialogue_panel(
+    dialogue: Res<ActiveDialogue>,
+    trees: Res<Assets<DialogueTree>>,
+    flags: Res<GameFlags>,
+    button_assets: Res<ButtonAssets>,
+    asset_server: Res<AssetServer>,
+    panel_query: Query<Entity, With<DialoguePanel>>,
+    button_query: Query<Entity, With<DialogueButton>>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut directional_nav_map: ResMut<DirectionalNavigationMap>,
+    mut input_focus: ResMut<InputFocus>,
+    mut commands: Commands,
+) {
+    for panel in &panel_query {
+        commands.entity(panel).despawn();
+    }
+    directional_nav_map.remove_multiple(button_query.iter().collect());
+
+    let Some(node) = trees
+        .get(&dialogue.tree)
+        .and_then(|tree| tree.get(&dialogue.node))
+    else {
+        return;
+    };
+
+    let line = node.lines.get(dialogue.line).cloned().unwrap_or_default();
+
+    let panel = commands
+        .spawn((
+            Name::new("Dialogue Panel"),
+            DialoguePanel,
+            Node {
+                position_type: PositionType::Absolute,
+                left: percent(10),
+                width: percent(80),
+                bottom: percent(4),
+                padding: UiRect::all(px(24)),
+                column_gap: px(24),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.8)),
+            GlobalZIndex(2),
+            DespawnOnExit(Menu::Dialogue),
+        ))
+        .id();
+
+    if let Some(portrait) = &node.portrait {
+        commands.entity(panel).with_child((
+            Name::new("Portrait"),
+            ImageNode::new(asset_server.load(portrait)),
+            Node {
+                width: px(120),
+                height: px(120),
+                flex_shrink: 0.0,
+                ..default()
+            },
+        ));
+    }
+
+    let content = commands
+        .spawn((
+            Name::new("Dialogue Content"),
+            Node {
+                flex_direction: FlexDirection::Column,
+                flex_grow: 1.0,
+                row_gap: px(12),
+                ..default()
+            },
+            children![widget::header(&node.speaker), widget::label(line)],
+        ))
+        .id();
+    commands.entity(panel).add_child(content);
+
+    let mut buttons = Vec::new();
+
+    // Without any available choice, the conversation continues as if there were none.
+    if dialogue.is_last_line(node) && node.has_available_choice(&flags) {
+        for (index, choice) in node.choices.iter().enumerate() {
+            if !choice.is_available(&flags) {
+                continue;
+            }
+
+            buttons.push(
+                commands
+                    .spawn((
+                        widget::button_wide(
+                            &button_assets,
+                            &mut texture_atlas_layouts,
+                            &choice.text,
+                            move |_: On<Pointer<Click>>, mut commands: Commands| {
+                                commands.trigger(ChooseDialogueEvent(index));
+                            },
+                        ),
+                        DialogueButton,
+                    ))
+                    .id(),
+            );
+        }
+    } else {
+        buttons.push(
+            commands
+                .spawn((
+                    widget::button_wide(
+                        &button_assets,
+                        &mut texture_atlas_layouts,
+                        "Continue",
+                        advance,
+                    ),
+                    DialogueButton,
+                ))
+                .id(),
+        );
+    }
+
+    commands.entity(content).add_children(&buttons);
+
+    directional_nav_map.add_looping_edges(&buttons, CompassOctant::South);
+    input_focus.0 = buttons.first().copied();
+}
+
+fn advance(_: On<Pointer<Click>>, mut commands: Commands) {
+    commands.trigger(AdvanceDialogueEvent);
+}
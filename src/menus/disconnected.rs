@@ -0,0 +1,136 @@
+//! The overlay shown when the gamepad disconnects during gameplay. The game stays paused
+//! until a gamepad is connected again, or the player carries on with the keyboard. In local
+//! co-op, it waits for the gamepad of every player who joined with one.
+
+use bevy::input_focus::InputFocus;
+use bevy::prelude::*;
+
+use crate::Pause;
+use crate::game::character::player::Player;
+use crate::gamepad::GamepadRes;
+use crate::input::{Action, ActionState, InputDevice, InputMap, InputSource, action_just_pressed};
+use crate::menus::Menu;
+use crate::screens::Screen;
+use crate::theme::widget::{self, ButtonAssets};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Disconnected), spawn_disconnected_menu);
+    app.add_systems(
+        Update,
+        (
+            open_disconnected_menu.run_if(
+                in_state(Screen::Gameplay)
+                    .and(in_state(Menu::None))
+                    .and(resource_removed::<GamepadRes>),
+            ),
+            open_disconnected_menu_for_co_op.run_if(
+                in_state(Screen::Gameplay)
+                    .and(in_state(Menu::None))
+                    .and(any_component_removed::<Gamepad>),
+            ),
+            resume_on_reconnect.run_if(
+                in_state(Menu::Disconnected)
+                    .and(resource_added::<GamepadRes>.or(any_match_filter::<Added<Gamepad>>)),
+            ),
+            resume.run_if(in_state(Menu::Disconnected).and(action_just_pressed(Action::Back))),
+        ),
+    );
+}
+
+fn open_disconnected_menu(
+    action_state: Res<ActionState>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    // Players on the keyboard don't need the gamepad to carry on.
+    if action_state.last_device != InputDevice::Gamepad {
+        return;
+    }
+
+    next_pause.set(Pause(true));
+    next_menu.set(Menu::Disconnected);
+}
+
+/// Opens when a co-op player's own gamepad disconnects, even if another gamepad is left.
+fn open_disconnected_menu_for_co_op(
+    player_query: Query<&InputSource, With<Player>>,
+    gamepad_query: Query<(), With<Gamepad>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    if !any_player_gamepad_missing(&player_query, &gamepad_query) {
+        return;
+    }
+
+    next_pause.set(Pause(true));
+    next_menu.set(Menu::Disconnected);
+}
+
+fn any_player_gamepad_missing(
+    player_query: &Query<&InputSource, With<Player>>,
+    gamepad_query: &Query<(), With<Gamepad>>,
+) -> bool {
+    player_query.iter().any(|source| match source {
+        InputSource::Gamepad(gamepad) => !gamepad_query.contains(*gamepad),
+        InputSource::All | InputSource::KeyboardMouse => false,
+    })
+}
+
+fn spawn_disconnected_menu(
+    input_map: Res<InputMap>,
+    button_assets: Res<ButtonAssets>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut input_focus: ResMut<InputFocus>,
+    mut commands: Commands,
+) {
+    let hint = match input_map.prompt(Action::Back, InputDevice::KeyboardMouse) {
+        Some(binding) => {
+            format!("Reconnect it to continue, or press {binding} to play with the keyboard")
+        }
+        None => "Reconnect it to continue".to_string(),
+    };
+
+    let ui_root = commands
+        .spawn((
+            widget::ui_root("Disconnected Menu"),
+            GlobalZIndex(2),
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+            DespawnOnExit(Menu::Disconnected),
+            children![
+                widget::header("Controller disconnected"),
+                widget::label(hint)
+            ],
+        ))
+        .id();
+
+    let keyboard_button = commands
+        .spawn(widget::button(
+            &button_assets,
+            &mut texture_atlas_layouts,
+            "Use keyboard",
+            resume_on_click,
+        ))
+        .id();
+    commands.entity(ui_root).add_child(keyboard_button);
+
+    input_focus.0 = Some(keyboard_button);
+}
+
+fn resume_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::None);
+}
+
+/// Waits until every co-op player has their gamepad back.
+fn resume_on_reconnect(
+    player_query: Query<&InputSource, With<Player>>,
+    gamepad_query: Query<(), With<Gamepad>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    if !any_player_gamepad_missing(&player_query, &gamepad_query) {
+        next_menu.set(Menu::None);
+    }
+}
+
+fn resume(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::None);
+}
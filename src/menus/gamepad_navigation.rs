@@ -1,4 +1,5 @@
 use crate::audio::sound_effect;
+use crate::input::{Action, ActionState, InputDevice, UpdateActions};
 use crate::theme::interaction::InteractionAssets;
 use crate::theme::prelude::InteractionPalette;
 use crate::theme::widget::ButtonRoot;
@@ -10,7 +11,6 @@ use bevy::input_focus::{InputDispatchPlugin, InputFocus, InputFocusVisible};
 use bevy::math::CompassOctant;
 use bevy::picking::backend::HitData;
 use bevy::picking::pointer::{Location, PointerId};
-use bevy::platform::collections::HashSet;
 use bevy::prelude::*;
 use std::time::Duration;
 
@@ -21,9 +21,10 @@ pub fn plugin(app: &mut App) {
         // This resource is canonically used to track whether or not to render a focus indicator
         // It starts as false, but we set it to true here as we would like to see the focus indicator
         .insert_resource(InputFocusVisible(true))
-        // We've made a simple resource to keep track of the actions that are currently being pressed for this example
-        .init_resource::<ActionState>()
-        .add_systems(PreUpdate, (process_inputs, navigate).chain())
+        .add_systems(
+            PreUpdate,
+            (process_inputs, navigate).chain().after(UpdateActions),
+        )
         .add_systems(
             Update,
             (
@@ -33,85 +34,20 @@ pub fn plugin(app: &mut App) {
         );
 }
 
-// The indirection between inputs and actions allows us to easily remap inputs
-// and handle multiple input sources (keyboard, gamepad, etc.) in our game
-#[derive(Debug, PartialEq, Eq, Hash)]
-enum DirectionalNavigationAction {
-    Up,
-    Down,
-    Left,
-    Right,
-    Select,
-}
-
-impl DirectionalNavigationAction {
-    fn variants() -> Vec<Self> {
-        vec![
-            DirectionalNavigationAction::Up,
-            DirectionalNavigationAction::Down,
-            DirectionalNavigationAction::Left,
-            DirectionalNavigationAction::Right,
-            DirectionalNavigationAction::Select,
-        ]
-    }
-
-    fn keycode(&self) -> KeyCode {
-        match self {
-            DirectionalNavigationAction::Up => KeyCode::ArrowUp,
-            DirectionalNavigationAction::Down => KeyCode::ArrowDown,
-            DirectionalNavigationAction::Left => KeyCode::ArrowLeft,
-            DirectionalNavigationAction::Right => KeyCode::ArrowRight,
-            DirectionalNavigationAction::Select => KeyCode::Enter,
-        }
-    }
-
-    fn gamepad_button(&self) -> GamepadButton {
-        match self {
-            DirectionalNavigationAction::Up => GamepadButton::DPadUp,
-            DirectionalNavigationAction::Down => GamepadButton::DPadDown,
-            DirectionalNavigationAction::Left => GamepadButton::DPadLeft,
-            DirectionalNavigationAction::Right => GamepadButton::DPadRight,
-            // This is the "A" button on an Xbox controller,
-            // and is conventionally used as the "Select" / "Interact" button in many games
-            DirectionalNavigationAction::Select => GamepadButton::South,
-        }
-    }
-}
-
-// This keeps track of the inputs that are currently being pressed
-#[derive(Default, Resource)]
-struct ActionState {
-    pressed_actions: HashSet<DirectionalNavigationAction>,
-}
-
-fn process_inputs(
-    mut action_state: ResMut<ActionState>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    gamepad_input: Query<&Gamepad>,
-    mut commands: Commands,
-) {
-    // Reset the set of pressed actions each frame
-    // to ensure that we only process each action once
-    action_state.pressed_actions.clear();
-
-    for action in DirectionalNavigationAction::variants() {
-        // Use just_pressed to ensure that we only process each action once
-        // for each time it is pressed
-        if keyboard_input.just_pressed(action.keycode()) {
-            commands.insert_resource(InputFocusVisible(true));
-            action_state.pressed_actions.insert(action);
-        }
-    }
-
-    // We're treating this like a single-player game:
-    // if multiple gamepads are connected, we don't care which one is being used
-    for gamepad in gamepad_input.iter() {
-        for action in DirectionalNavigationAction::variants() {
-            // Unlike keyboard input, gamepads are bound to a specific controller
-            if gamepad.just_pressed(action.gamepad_button()) {
-                action_state.pressed_actions.insert(action);
-            }
-        }
+// The input map decides which keys and buttons navigate, so they can be rebound
+fn process_inputs(action_state: Res<ActionState>, mut commands: Commands) {
+    let navigating = [
+        Action::NavigateUp,
+        Action::NavigateDown,
+        Action::NavigateLeft,
+        Action::NavigateRight,
+        Action::Select,
+    ]
+    .into_iter()
+    .any(|action| action_state.just_pressed(action));
+
+    if navigating && action_state.last_device == InputDevice::KeyboardMouse {
+        commands.insert_resource(InputFocusVisible(true));
     }
 }
 
@@ -123,19 +59,13 @@ fn navigate(
 ) {
     // If the user is pressing both left and right, or up and down,
     // we should not move in either direction.
-    let net_east_west = action_state
-        .pressed_actions
-        .contains(&DirectionalNavigationAction::Right) as i8
-        - action_state
-            .pressed_actions
-            .contains(&DirectionalNavigationAction::Left) as i8;
+    // Use just_pressed to ensure that we only process each action once
+    // for each time it is pressed
+    let net_east_west = action_state.just_pressed(Action::NavigateRight) as i8
+        - action_state.just_pressed(Action::NavigateLeft) as i8;
 
-    let net_north_south = action_state
-        .pressed_actions
-        .contains(&DirectionalNavigationAction::Up) as i8
-        - action_state
-            .pressed_actions
-            .contains(&DirectionalNavigationAction::Down) as i8;
+    let net_north_south = action_state.just_pressed(Action::NavigateUp) as i8
+        - action_state.just_pressed(Action::NavigateDown) as i8;
 
     // Compute the direction that the user is trying to navigate in
     let maybe_direction = match (net_east_west, net_north_south) {
@@ -176,9 +106,7 @@ fn interact_with_focused_button(
     mut button_query: Query<(&mut ImageNode, &InteractionPalette), With<ButtonRoot>>,
     mut commands: Commands,
 ) {
-    if action_state
-        .pressed_actions
-        .contains(&DirectionalNavigationAction::Select)
+    if action_state.just_pressed(Action::Select)
         && let Some(focused_entity) = input_focus.0
         && let Ok(children) = children.get(focused_entity)
     {
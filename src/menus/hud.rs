@@ -3,6 +3,10 @@ use crate::asset_tracking::LoadResource;
 use crate::game::character::health::Health;
 use crate::game::character::player::Player;
 use crate::game::character::stamina::Stamina;
+use crate::game::item::inventory::Inventory;
+use crate::game::item::{ItemAssets, ItemDatabase};
+use crate::game::party::Party;
+use crate::game::quest::{QuestAssets, QuestDatabase, QuestLog};
 use crate::screens::Screen;
 use crate::theme::prelude::*;
 use bevy::prelude::*;
@@ -13,7 +17,24 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Gameplay), spawn_hud);
     app.add_systems(
         Update,
-        (update_health_bar, update_stamina_bar)
+        (
+            (
+                spawn_player_stat_bars,
+                spawn_player_item_bars,
+                despawn_player_bars,
+            ),
+            (update_health_bar, update_stamina_bar),
+        )
+            .chain()
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Respond),
+    );
+    app.add_systems(
+        Update,
+        (
+            update_item_bar,
+            update_quest_tracker.run_if(resource_changed::<QuestLog>),
+        )
             .run_if(in_state(Screen::Gameplay))
             .in_set(AppSystems::Respond),
     );
@@ -36,9 +57,12 @@ fn spawn_hud(
         .with_children(|parent| {
             // Add your HUD elements here
             parent.spawn(stat_bars(&mut texture_atlas_layouts));
+            parent.spawn(item_bars());
+            parent.spawn(quest_tracker());
         });
 }
 
+/// Holds the stat bars of each player, one below the other.
 fn stat_bars(texture_atlas_layouts: &mut Assets<TextureAtlasLayout>) -> impl Bundle {
     let layout = TextureAtlasLayout::from_grid(UVec2::new(4, 8), 8, 8, None, None);
     let layout = texture_atlas_layouts.add(layout);
@@ -53,30 +77,164 @@ fn stat_bars(texture_atlas_layouts: &mut Assets<TextureAtlasLayout>) -> impl Bun
             width: percent(78),
 
             top: percent(5),
-            height: percent(10),
+            row_gap: px(12),
 
             ..default()
         },
-        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
-            parent.spawn((HealthBar, Node::default()));
-            parent.spawn((StaminaBar, Node::default()));
-        })),
+    )
+}
+
+/// Adds stat bars for each newly spawned player. They are labelled with the player's number
+/// in local co-op.
+fn spawn_player_stat_bars(
+    party: Res<Party>,
+    player_query: Query<(Entity, &Player), Added<Player>>,
+    stat_bars_query: Query<Entity, With<StatBarLayout>>,
+    mut commands: Commands,
+) {
+    let Ok(stat_bars) = stat_bars_query.single() else {
+        return;
+    };
+
+    let mut players = player_query.iter().collect::<Vec<_>>();
+    players.sort_by_key(|(_, player)| player.index);
+    let labelled = party.sources().len() > 1;
+
+    for (entity, player) in players {
+        commands.entity(stat_bars).with_children(|parent| {
+            let mut bars = parent.spawn((
+                Name::new("Player Stat Bars"),
+                PlayerStatBars(entity),
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+            ));
+
+            bars.with_children(|parent| {
+                if labelled {
+                    parent.spawn((
+                        Text::new(format!("P{}", player.index + 1)),
+                        TextFont::from_font_size(20.0),
+                        TextColor(player.color()),
+                    ));
+                }
+                parent.spawn((HealthBar(entity), Node::default()));
+                parent.spawn((StaminaBar(entity), Node::default()));
+            });
+        });
+    }
+}
+
+/// Adds an item bar for each newly spawned player, in the same order as the stat bars.
+fn spawn_player_item_bars(
+    player_query: Query<(Entity, &Player), Added<Player>>,
+    item_bars_query: Query<Entity, With<ItemBars>>,
+    mut commands: Commands,
+) {
+    let Ok(item_bars) = item_bars_query.single() else {
+        return;
+    };
+
+    let mut players = player_query.iter().collect::<Vec<_>>();
+    players.sort_by_key(|(_, player)| player.index);
+
+    for (entity, _) in players {
+        commands.entity(item_bars).with_child((
+            Name::new("Player Item Bar"),
+            ItemBar(entity),
+            Node {
+                column_gap: px(8),
+                ..default()
+            },
+        ));
+    }
+}
+
+/// Removes the stat and item bars of players that are gone.
+fn despawn_player_bars(
+    mut removed: RemovedComponents<Player>,
+    stat_bars_query: Query<(Entity, &PlayerStatBars)>,
+    item_bar_query: Query<(Entity, &ItemBar)>,
+    mut commands: Commands,
+) {
+    for player in removed.read() {
+        let stat_bars = stat_bars_query
+            .iter()
+            .map(|(bars, PlayerStatBars(owner))| (bars, *owner));
+        let item_bars = item_bar_query
+            .iter()
+            .map(|(bar, ItemBar(owner))| (bar, *owner));
+
+        for (bars, owner) in stat_bars.chain(item_bars) {
+            if owner == player {
+                commands.entity(bars).despawn();
+            }
+        }
+    }
+}
+
+/// Holds the item bar of each player, one above the other.
+fn item_bars() -> impl Bundle {
+    (
+        ItemBars,
+        Node {
+            position_type: PositionType::Absolute,
+            flex_direction: FlexDirection::Column,
+            left: percent(2),
+            bottom: percent(4),
+            row_gap: px(8),
+            ..default()
+        },
+    )
+}
+
+/// Shows the current objective of the oldest active quest.
+fn quest_tracker() -> impl Bundle {
+    (
+        QuestTracker,
+        Node {
+            position_type: PositionType::Absolute,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::End,
+            right: percent(2),
+            top: percent(5),
+            max_width: percent(30),
+            row_gap: px(4),
+            ..default()
+        },
     )
 }
 
 #[derive(Component, Debug, Clone)]
 struct StatBarLayout(Handle<TextureAtlasLayout>);
 
+/// Holds the stat bars of the player entity.
+#[derive(Component, Debug, Clone, Copy)]
+struct PlayerStatBars(Entity);
+
+/// Shows the health of the player entity.
 #[derive(Component, Debug, Clone, Copy)]
-struct HealthBar;
+struct HealthBar(Entity);
 #[derive(Component, Debug, Clone, Copy)]
 struct HealthBarSegment;
 
+/// Shows the stamina of the player entity.
 #[derive(Component, Debug, Clone, Copy)]
-struct StaminaBar;
+struct StaminaBar(Entity);
 #[derive(Component, Debug, Clone, Copy)]
 struct StaminaBarSegment;
 
+#[derive(Component, Debug, Clone, Copy)]
+struct ItemBars;
+
+/// Shows the first few slots of the player entity's inventory.
+#[derive(Component, Debug, Clone, Copy)]
+struct ItemBar(Entity);
+
+#[derive(Component, Debug, Clone, Copy)]
+struct QuestTracker;
+
 #[derive(Resource, Asset, Clone, Reflect)]
 pub struct StatBarAssets {
     #[dependency]
@@ -97,81 +255,198 @@ const HEALTH_BAR_PIXEL_VALUE: usize = 10;
 fn update_health_bar(
     player_query: Query<&Health, With<Player>>,
     stat_bar_layout_query: Query<&StatBarLayout>,
-    health_bar_query: Query<Entity, With<HealthBar>>,
-    segment_query: Query<Entity, With<HealthBarSegment>>,
+    health_bar_query: Query<(Entity, &HealthBar)>,
     stat_bar_assets: Res<StatBarAssets>,
     mut commands: Commands,
 ) {
-    let Ok(health) = player_query.single() else {
-        return;
-    };
-
     let Ok(bar_layout) = stat_bar_layout_query.single() else {
         return;
     };
 
-    let Ok(bar_entity) = health_bar_query.single() else {
-        return;
-    };
-
-    segment_query
-        .iter()
-        .for_each(|segment| commands.entity(segment).despawn());
-
     let texture_atlas_layout = bar_layout.0.clone();
 
-    spawn_stat_bar(
-        health.max,
-        health.current,
-        HEALTH_BAR_PIXEL_VALUE,
-        8,
-        HealthBarSegment,
-        bar_entity,
-        &stat_bar_assets,
-        &texture_atlas_layout,
-        commands,
-    )
+    for (bar_entity, HealthBar(player)) in &health_bar_query {
+        let Ok(health) = player_query.get(*player) else {
+            continue;
+        };
+
+        commands.entity(bar_entity).despawn_children();
+
+        spawn_stat_bar(
+            health.max,
+            health.current(),
+            HEALTH_BAR_PIXEL_VALUE,
+            8,
+            HealthBarSegment,
+            bar_entity,
+            &stat_bar_assets,
+            &texture_atlas_layout,
+            &mut commands,
+        )
+    }
 }
 
 const STAMINA_BAR_PIXEL_VALUE: usize = 10;
+/// The stamina bar turns orange while the player is exhausted.
+const EXHAUSTED_STAMINA_BAR_SPRITE_INDEX: usize = 32;
 
 fn update_stamina_bar(
     player_query: Query<&Stamina, With<Player>>,
     stat_bar_layout_query: Query<&StatBarLayout>,
-    health_bar_query: Query<Entity, With<StaminaBar>>,
-    segment_query: Query<Entity, With<StaminaBarSegment>>,
+    stamina_bar_query: Query<(Entity, &StaminaBar)>,
     stat_bar_assets: Res<StatBarAssets>,
     mut commands: Commands,
 ) {
-    let Ok(stamina) = player_query.single() else {
+    let Ok(bar_layout) = stat_bar_layout_query.single() else {
         return;
     };
 
-    let Ok(bar_layout) = stat_bar_layout_query.single() else {
+    let texture_atlas_layout = bar_layout.0.clone();
+
+    for (bar_entity, StaminaBar(player)) in &stamina_bar_query {
+        let Ok(stamina) = player_query.get(*player) else {
+            continue;
+        };
+
+        commands.entity(bar_entity).despawn_children();
+
+        spawn_stat_bar(
+            stamina.max,
+            stamina.current(),
+            STAMINA_BAR_PIXEL_VALUE,
+            if stamina.exhausted {
+                EXHAUSTED_STAMINA_BAR_SPRITE_INDEX
+            } else {
+                16
+            },
+            StaminaBarSegment,
+            bar_entity,
+            &stat_bar_assets,
+            &texture_atlas_layout,
+            &mut commands,
+        )
+    }
+}
+
+const ITEM_BAR_SLOTS: usize = 5;
+const ITEM_ICON_SIZE: f32 = 48.0;
+
+fn update_item_bar(
+    player_query: Query<Ref<Inventory>, With<Player>>,
+    item_bar_query: Query<(Entity, Ref<ItemBar>)>,
+    item_assets: Res<ItemAssets>,
+    databases: Res<Assets<ItemDatabase>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let Some(database) = databases.get(&item_assets.database) else {
         return;
     };
 
-    let Ok(bar_entity) = health_bar_query.single() else {
+    for (item_bar, owner) in &item_bar_query {
+        let Ok(inventory) = player_query.get(owner.0) else {
+            continue;
+        };
+
+        if inventory.is_changed() || owner.is_added() {
+            spawn_item_slots(item_bar, &inventory, database, &asset_server, &mut commands);
+        }
+    }
+}
+
+fn spawn_item_slots(
+    item_bar: Entity,
+    inventory: &Inventory,
+    database: &ItemDatabase,
+    asset_server: &AssetServer,
+    commands: &mut Commands,
+) {
+    commands.entity(item_bar).despawn_children();
+    commands.entity(item_bar).with_children(|parent| {
+        for slot in inventory.slots().iter().take(ITEM_BAR_SLOTS) {
+            let icon = slot
+                .as_ref()
+                .and_then(|stack| database.get(&stack.item))
+                .map(|definition| asset_server.load(&definition.icon));
+            let count = slot
+                .as_ref()
+                .filter(|stack| stack.count > 1)
+                .map(|stack| stack.count.to_string())
+                .unwrap_or_default();
+
+            parent.spawn((
+                Node {
+                    width: px(ITEM_ICON_SIZE),
+                    height: px(ITEM_ICON_SIZE),
+                    align_items: AlignItems::End,
+                    justify_content: JustifyContent::End,
+                    ..default()
+                },
+                BackgroundColor(Color::BLACK.with_alpha(0.4)),
+                children![
+                    (
+                        Node {
+                            position_type: PositionType::Absolute,
+                            width: percent(100),
+                            height: percent(100),
+                            ..default()
+                        },
+                        ImageNode {
+                            image: icon.unwrap_or_default(),
+                            color: if slot.is_some() {
+                                Color::WHITE
+                            } else {
+                                Color::NONE
+                            },
+                            ..default()
+                        },
+                    ),
+                    (Text::new(count), TextFont::from_font_size(16.0)),
+                ],
+            ));
+        }
+    });
+}
+
+fn update_quest_tracker(
+    log: Res<QuestLog>,
+    tracker_query: Query<Entity, With<QuestTracker>>,
+    quest_assets: Res<QuestAssets>,
+    databases: Res<Assets<QuestDatabase>>,
+    mut commands: Commands,
+) {
+    let Ok(tracker) = tracker_query.single() else {
         return;
     };
 
-    segment_query
-        .iter()
-        .for_each(|segment| commands.entity(segment).despawn());
+    commands.entity(tracker).despawn_children();
 
-    let texture_atlas_layout = bar_layout.0.clone();
+    let Some(database) = databases.get(&quest_assets.database) else {
+        return;
+    };
 
-    spawn_stat_bar(
-        stamina.max,
-        stamina.current as usize,
-        HEALTH_BAR_PIXEL_VALUE,
-        16,
-        StaminaBarSegment,
-        bar_entity,
-        &stat_bar_assets,
-        &texture_atlas_layout,
-        commands,
-    )
+    let tracked = log.active().find_map(|quest| {
+        let definition = database.get(&quest.id)?;
+        Some((definition.name.clone(), quest.objective_text(definition)?))
+    });
+
+    let Some((name, objective)) = tracked else {
+        return;
+    };
+
+    commands.entity(tracker).with_children(|parent| {
+        parent.spawn((
+            Text::new(name),
+            TextFont::from_font_size(24.0),
+            TextColor(Color::WHITE),
+        ));
+        parent.spawn((
+            Text::new(objective),
+            TextFont::from_font_size(18.0),
+            TextColor(Color::WHITE.with_alpha(0.8)),
+            TextLayout::new_with_justify(Justify::Right),
+        ));
+    });
 }
 
 fn spawn_stat_bar(
@@ -183,7 +458,7 @@ fn spawn_stat_bar(
     parent: Entity,
     stat_bar_assets: &StatBarAssets,
     texture_atlas_layout: &Handle<TextureAtlasLayout>,
-    mut commands: Commands,
+    commands: &mut Commands,
 ) {
     let segment_value = pixel_value * 3;
 
@@ -0,0 +1,186 @@
+//! The join screen for local co-op. Each player presses select on their gamepad or the
+//! keyboard to join, then anyone who joined presses pause to start. Opened from the main menu.
+
+use bevy::input_focus::InputFocus;
+use bevy::prelude::*;
+
+use crate::asset_tracking::ResourceHandles;
+use crate::game::party::{MAX_PLAYERS, Party};
+use crate::input::{Action, InputDevice, InputMap, InputSource};
+use crate::menus::Menu;
+use crate::screens::Screen;
+use crate::theme::palette::LABEL_TEXT;
+use crate::theme::widget::{self, ButtonAssets};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Join), spawn_join_menu);
+    app.add_systems(
+        Update,
+        (
+            join_or_leave,
+            update_slots.run_if(resource_changed::<Party>),
+        )
+            .chain()
+            .run_if(in_state(Menu::Join)),
+    );
+}
+
+/// The text of one of the player slots.
+#[derive(Component, Debug, Clone, Copy)]
+struct JoinSlot(usize);
+
+fn spawn_join_menu(
+    input_map: Res<InputMap>,
+    button_assets: Res<ButtonAssets>,
+    mut party: ResMut<Party>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut input_focus: ResMut<InputFocus>,
+    mut commands: Commands,
+) {
+    party.clear();
+
+    let hint = match (
+        input_map.prompt(Action::Select, InputDevice::KeyboardMouse),
+        input_map.prompt(Action::Select, InputDevice::Gamepad),
+        input_map.prompt(Action::Pause, InputDevice::KeyboardMouse),
+        input_map.prompt(Action::Pause, InputDevice::Gamepad),
+    ) {
+        (Some(key), Some(button), Some(start_key), Some(start_button)) => {
+            format!("Press {key} or {button} to join, then {start_key} or {start_button} to start")
+        }
+        _ => "Press select to join, then pause to start".to_string(),
+    };
+
+    let ui_root = commands
+        .spawn((
+            widget::ui_root("Join Menu"),
+            GlobalZIndex(2),
+            DespawnOnExit(Menu::Join),
+            children![widget::header("Co-op"), widget::label(hint)],
+        ))
+        .id();
+
+    for slot in 0..MAX_PLAYERS {
+        commands.entity(ui_root).with_child((
+            Name::new("Join Slot"),
+            JoinSlot(slot),
+            Text(slot_text(&party, slot)),
+            TextFont::from_font_size(28.0),
+            TextColor(LABEL_TEXT),
+        ));
+    }
+
+    let start_button = commands
+        .spawn(widget::button(
+            &button_assets,
+            &mut texture_atlas_layouts,
+            "Start",
+            start_on_click,
+        ))
+        .id();
+    let back_button = commands
+        .spawn(widget::button(
+            &button_assets,
+            &mut texture_atlas_layouts,
+            "Back",
+            go_back_on_click,
+        ))
+        .id();
+    let button_row = commands
+        .spawn((
+            Name::new("Join Buttons"),
+            Node {
+                column_gap: px(20),
+                ..default()
+            },
+        ))
+        .add_children(&[start_button, back_button])
+        .id();
+    commands.entity(ui_root).add_child(button_row);
+
+    // Select joins instead of pressing a button.
+    input_focus.0 = None;
+}
+
+/// E.g. "P2: Gamepad", or an invitation to join if nobody has taken the slot.
+fn slot_text(party: &Party, slot: usize) -> String {
+    let device = match party.members().get(slot) {
+        Some(InputSource::KeyboardMouse) => "Keyboard",
+        Some(InputSource::Gamepad(_)) => "Gamepad",
+        Some(InputSource::All) => "Any device",
+        None => "Press to join",
+    };
+    format!("P{}: {device}", slot + 1)
+}
+
+/// Reads every device on its own, since each joins or leaves as a separate player.
+fn join_or_leave(
+    input_map: Res<InputMap>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<(Entity, &Gamepad)>,
+    resource_handles: Res<ResourceHandles>,
+    mut party: ResMut<Party>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    let keyboard_mouse = (
+        InputSource::KeyboardMouse,
+        Some(&*keys),
+        Some(&*mouse),
+        None,
+    );
+    let devices = std::iter::once(keyboard_mouse).chain(
+        gamepads
+            .iter()
+            .map(|(entity, gamepad)| (InputSource::Gamepad(entity), None, None, Some(gamepad))),
+    );
+
+    for (source, keys, mouse, gamepad) in devices {
+        let just_pressed = |action| input_map.just_pressed(action, keys, mouse, gamepad);
+
+        // Starting comes first so a binding shared with back or select can't swallow it.
+        if just_pressed(Action::Pause) && !party.members().is_empty() {
+            start(&resource_handles, &mut next_screen);
+            return;
+        } else if just_pressed(Action::Back) {
+            if party.contains(source) {
+                party.leave(source);
+            } else {
+                next_menu.set(Menu::Main);
+                return;
+            }
+        } else if just_pressed(Action::Select) {
+            party.join(source);
+        }
+    }
+}
+
+fn update_slots(party: Res<Party>, mut slots: Query<(&JoinSlot, &mut Text)>) {
+    for (JoinSlot(slot), mut text) in &mut slots {
+        text.0 = slot_text(&party, *slot);
+    }
+}
+
+fn start(resource_handles: &ResourceHandles, next_screen: &mut NextState<Screen>) {
+    if resource_handles.is_all_done() {
+        next_screen.set(Screen::Gameplay);
+    } else {
+        next_screen.set(Screen::Loading);
+    }
+}
+
+fn start_on_click(
+    _: On<Pointer<Click>>,
+    party: Res<Party>,
+    resource_handles: Res<ResourceHandles>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if !party.members().is_empty() {
+        start(&resource_handles, &mut next_screen);
+    }
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
@@ -1,6 +1,9 @@
 //! The main menu (seen on the title screen).
 
-use crate::{asset_tracking::ResourceHandles, menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    asset_tracking::ResourceHandles, game::party::Party, menus::Menu, screens::Screen,
+    theme::widget,
+};
 use bevy::input_focus::InputFocus;
 use bevy::input_focus::directional_navigation::DirectionalNavigationMap;
 use bevy::math::CompassOctant;
@@ -37,6 +40,16 @@ fn spawn_main_menu(
             .id();
         commands.entity(ui_root).add_child(play_button);
 
+        let co_op_button = commands
+            .spawn(widget::button(
+                &button_assets,
+                &mut texture_atlas_layouts,
+                "Co-op",
+                open_join_menu,
+            ))
+            .id();
+        commands.entity(ui_root).add_child(co_op_button);
+
         let settings_button = commands
             .spawn(widget::button(
                 &button_assets,
@@ -68,7 +81,13 @@ fn spawn_main_menu(
         commands.entity(ui_root).add_child(exit_button);
 
         directional_nav_map.add_looping_edges(
-            &[play_button, settings_button, credits_button, exit_button],
+            &[
+                play_button,
+                co_op_button,
+                settings_button,
+                credits_button,
+                exit_button,
+            ],
             CompassOctant::South,
         );
 
@@ -84,6 +103,14 @@ fn spawn_main_menu(
             .id();
         commands.entity(ui_root).add_child(play_button);
 
+        let co_op_button = commands
+            .spawn(widget::button(
+                "Co-op",
+                crate::menus::main_menu::open_join_menu,
+            ))
+            .id();
+        commands.entity(ui_root).add_child(co_op_button);
+
         let settings_button = commands
             .spawn(widget::button(
                 "Settings",
@@ -101,7 +128,7 @@ fn spawn_main_menu(
         commands.entity(ui_root).add_child(credits_button);
 
         directional_nav_map.add_looping_edges(
-            &[play_button, settings_button, credits_button],
+            &[play_button, co_op_button, settings_button, credits_button],
             CompassOctant::South,
         );
 
@@ -112,8 +139,12 @@ fn spawn_main_menu(
 fn enter_loading_or_gameplay_screen(
     _: On<Pointer<Click>>,
     resource_handles: Res<ResourceHandles>,
+    mut party: ResMut<Party>,
     mut next_screen: ResMut<NextState<Screen>>,
 ) {
+    // A single player, playing with every device.
+    party.clear();
+
     if resource_handles.is_all_done() {
         next_screen.set(Screen::Gameplay);
     } else {
@@ -121,6 +152,10 @@ fn enter_loading_or_gameplay_screen(
     }
 }
 
+fn open_join_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Join);
+}
+
 fn open_settings_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Settings);
 }
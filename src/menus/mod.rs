@@ -1,10 +1,15 @@
 //! The game's menus and transitions between them.
 
+mod controls;
 mod credits;
+mod dialogue;
+mod disconnected;
 mod gamepad_navigation;
 mod hud;
+mod join;
 mod main_menu;
 mod pause;
+mod quests;
 mod settings;
 
 use bevy::prelude::*;
@@ -13,11 +18,16 @@ pub(super) fn plugin(app: &mut App) {
     app.init_state::<Menu>();
 
     app.add_plugins((
+        controls::plugin,
         credits::plugin,
+        dialogue::plugin,
+        disconnected::plugin,
         hud::plugin,
+        join::plugin,
         main_menu::plugin,
         settings::plugin,
         pause::plugin,
+        quests::plugin,
         gamepad_navigation::plugin,
     ));
 }
@@ -27,7 +37,12 @@ pub enum Menu {
     #[default]
     None,
     Main,
+    Join,
     Credits,
     Settings,
+    Controls,
     Pause,
+    Dialogue,
+    Quests,
+    Disconnected,
 }
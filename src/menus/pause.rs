@@ -1,23 +1,20 @@
 //! The pause menu.
 
-use crate::gamepad::gamepad_just_pressed;
+use crate::input::{Action, action_just_pressed};
 use crate::theme::widget::ButtonAssets;
 use crate::{menus::Menu, screens::Screen, theme::widget};
 use bevy::input_focus::InputFocus;
 use bevy::input_focus::directional_navigation::DirectionalNavigationMap;
 use bevy::math::CompassOctant;
-use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Pause), spawn_pause_menu);
     app.add_systems(
         Update,
         go_back.run_if(
-            in_state(Menu::Pause).and(
-                input_just_pressed(KeyCode::Escape)
-                    .or(gamepad_just_pressed(GamepadButton::East))
-                    .or(gamepad_just_pressed(GamepadButton::Start)),
-            ),
+            in_state(Menu::Pause)
+                .and(action_just_pressed(Action::Back).or(action_just_pressed(Action::Pause))),
         ),
     );
 }
@@ -48,6 +45,16 @@ fn spawn_pause_menu(
         .id();
     commands.entity(ui_root).add_child(continue_button);
 
+    let quests_button = commands
+        .spawn(widget::button(
+            &button_assets,
+            &mut texture_atlas_layouts,
+            "Quests",
+            open_quests_menu,
+        ))
+        .id();
+    commands.entity(ui_root).add_child(quests_button);
+
     let settings_button = commands
         .spawn(widget::button(
             &button_assets,
@@ -69,13 +76,17 @@ fn spawn_pause_menu(
     commands.entity(ui_root).add_child(quit_button);
 
     directional_nav_map.add_looping_edges(
-        &[continue_button, settings_button, quit_button],
+        &[continue_button, quests_button, settings_button, quit_button],
         CompassOctant::South,
     );
 
     input_focus.0 = Some(continue_button);
 }
 
+fn open_quests_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Quests);
+}
+
 fn open_settings_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Settings);
 }
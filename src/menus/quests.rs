@@ -0,0 +1,129 @@
+//! The quest log, listing active and completed quests. Opened from the pause menu.
+
+use bevy::input_focus::InputFocus;
+use bevy::prelude::*;
+
+use crate::game::quest::{QuestAssets, QuestDatabase, QuestLog, QuestState};
+use crate::input::{Action, action_just_pressed};
+use crate::menus::Menu;
+use crate::theme::palette::LABEL_TEXT;
+use crate::theme::widget::{self, ButtonAssets};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Quests), spawn_quests_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::Quests).and(action_just_pressed(Action::Back))),
+    );
+}
+
+fn spawn_quests_menu(
+    log: Res<QuestLog>,
+    quest_assets: Res<QuestAssets>,
+    databases: Res<Assets<QuestDatabase>>,
+    button_assets: Res<ButtonAssets>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut input_focus: ResMut<InputFocus>,
+    mut commands: Commands,
+) {
+    let ui_root = commands
+        .spawn((
+            widget::scrollable_ui_root("Quests Menu"),
+            GlobalZIndex(2),
+            DespawnOnExit(Menu::Quests),
+            children![widget::header("Quests")],
+        ))
+        .id();
+
+    let database = databases.get(&quest_assets.database);
+
+    for (title, quests) in [
+        ("Active", log.active().collect::<Vec<_>>()),
+        ("Completed", log.completed().collect()),
+    ] {
+        let section = commands.spawn(quest_section(title, &quests, database)).id();
+        commands.entity(ui_root).add_child(section);
+    }
+
+    let back_button = commands
+        .spawn(widget::button(
+            &button_assets,
+            &mut texture_atlas_layouts,
+            "Back",
+            go_back_on_click,
+        ))
+        .id();
+    commands.entity(ui_root).add_child(back_button);
+
+    input_focus.0 = Some(back_button);
+}
+
+fn quest_section(
+    title: &str,
+    quests: &[&QuestState],
+    database: Option<&QuestDatabase>,
+) -> impl Bundle {
+    let entries = quests
+        .iter()
+        .filter_map(|quest| {
+            let definition = database?.get(&quest.id)?;
+            Some((
+                definition.name.clone(),
+                quest
+                    .objective_text(definition)
+                    .unwrap_or_else(|| definition.description.clone()),
+            ))
+        })
+        .collect::<Vec<_>>();
+    let title = title.to_string();
+
+    (
+        Name::new(format!("{title} Quests")),
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: px(8),
+            width: px(600),
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn(widget::label(title));
+
+            if entries.is_empty() {
+                parent.spawn(quest_text("None yet", 18.0));
+            }
+
+            for (name, details) in entries {
+                parent.spawn((
+                    Name::new("Quest Entry"),
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(px(8)),
+                        width: percent(100),
+                        ..default()
+                    },
+                    BackgroundColor(Color::BLACK.with_alpha(0.4)),
+                    children![quest_text(name, 22.0), quest_text(details, 16.0)],
+                ));
+            }
+        })),
+    )
+}
+
+fn quest_text(text: impl Into<String>, size: f32) -> impl Bundle {
+    (
+        Text(text.into()),
+        TextFont::from_font_size(size),
+        TextColor(LABEL_TEXT),
+        TextLayout::new_with_justify(Justify::Center),
+    )
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Pause);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Pause);
+}
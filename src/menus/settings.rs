@@ -2,42 +2,42 @@
 //!
 //! Additional settings and accessibility options should go here.
 
-use crate::gamepad::gamepad_just_pressed;
+use crate::input::{Action, InputMap, ResponseCurve, Stick, StickSettings, action_just_pressed};
 use crate::theme::widget;
 use crate::theme::widget::ButtonAssets;
 use crate::{menus::Menu, screens::Screen};
 use bevy::input_focus::InputFocus;
 use bevy::input_focus::directional_navigation::DirectionalNavigationMap;
-use bevy::{audio::Volume, input::common_conditions::input_just_pressed, prelude::*};
+use bevy::math::CompassOctant;
+use bevy::{audio::Volume, prelude::*};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Settings), spawn_settings_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Settings).and(
-            input_just_pressed(KeyCode::Escape).or(gamepad_just_pressed(GamepadButton::East)),
-        )),
+        go_back.run_if(in_state(Menu::Settings).and(action_just_pressed(Action::Back))),
     );
 
     app.add_systems(
         Update,
-        update_global_volume_label.run_if(in_state(Menu::Settings)),
+        (
+            update_global_volume_label,
+            update_stick_setting_labels.run_if(
+                resource_changed::<InputMap>.or(any_match_filter::<Added<StickSettingLabel>>),
+            ),
+        )
+            .run_if(in_state(Menu::Settings)),
     );
 }
 
 fn spawn_settings_menu(
     button_assets: Res<ButtonAssets>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
-    directional_nav_map: ResMut<DirectionalNavigationMap>,
+    mut directional_nav_map: ResMut<DirectionalNavigationMap>,
     mut input_focus: ResMut<InputFocus>,
     mut commands: Commands,
 ) {
-    let grid = settings_grid(
-        &button_assets,
-        &mut texture_atlas_layouts,
-        directional_nav_map,
-        &mut commands,
-    );
+    let (grid, steppers) = settings_grid(&button_assets, &mut texture_atlas_layouts, &mut commands);
 
     let ui_root = commands
         .spawn((
@@ -50,6 +50,16 @@ fn spawn_settings_menu(
 
     commands.entity(ui_root).add_child(grid);
 
+    let controls_button = commands
+        .spawn(widget::button(
+            &button_assets,
+            &mut texture_atlas_layouts,
+            "Controls",
+            open_controls_menu,
+        ))
+        .id();
+    commands.entity(ui_root).add_child(controls_button);
+
     let back_button = commands
         .spawn(widget::button(
             &button_assets,
@@ -60,22 +70,32 @@ fn spawn_settings_menu(
         .id();
     commands.entity(ui_root).add_child(back_button);
 
+    // The lower and raise buttons of each setting form a column each, and both lead on to
+    // the buttons below the grid.
+    let lower_buttons = steppers.iter().map(|[lower, _]| *lower).collect::<Vec<_>>();
+    let raise_buttons = steppers.iter().map(|[_, raise]| *raise).collect::<Vec<_>>();
+    directional_nav_map.add_looping_edges(
+        &[lower_buttons.as_slice(), &[controls_button, back_button]].concat(),
+        CompassOctant::South,
+    );
+    directional_nav_map.add_edges(&raise_buttons, CompassOctant::South);
+    for [lower, raise] in steppers {
+        directional_nav_map.add_symmetrical_edge(lower, raise, CompassOctant::East);
+    }
+    if let (Some(first), Some(last)) = (raise_buttons.first(), raise_buttons.last()) {
+        directional_nav_map.add_edge(*first, back_button, CompassOctant::North);
+        directional_nav_map.add_edge(*last, controls_button, CompassOctant::South);
+    }
+
     input_focus.0 = Some(back_button);
 }
 
+/// The grid of settings, and the lower and raise buttons of each row.
 fn settings_grid(
     button_assets: &ButtonAssets,
     texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
-    directional_nav_map: ResMut<DirectionalNavigationMap>,
     commands: &mut Commands,
-) -> Entity {
-    let volume_widget = global_volume_widget(
-        button_assets,
-        texture_atlas_layouts,
-        directional_nav_map,
-        commands,
-    );
-
+) -> (Entity, Vec<[Entity; 2]>) {
     let ui_root = commands
         .spawn((
             Name::new("Settings Grid"),
@@ -86,27 +106,49 @@ fn settings_grid(
                 grid_template_columns: RepeatedGridTrack::px(2, 400.0),
                 ..default()
             },
-            children![(
-                widget::label("Master Volume"),
-                Node {
-                    justify_self: JustifySelf::End,
-                    ..default()
-                }
-            ),],
         ))
         .id();
 
-    commands.entity(ui_root).add_child(volume_widget);
+    let (volume_widget, volume_buttons) =
+        global_volume_widget(button_assets, texture_atlas_layouts, commands);
+    let mut rows = vec![("Master Volume".to_string(), volume_widget)];
+    let mut steppers = vec![volume_buttons];
+
+    for stick in Stick::ALL {
+        for setting in StickSetting::ALL {
+            let (widget, buttons) = stick_setting_widget(
+                button_assets,
+                texture_atlas_layouts,
+                commands,
+                stick,
+                setting,
+            );
+            rows.push((format!("{} {}", stick.name(), setting.name()), widget));
+            steppers.push(buttons);
+        }
+    }
 
-    ui_root
+    for (name, widget) in rows {
+        let label = commands
+            .spawn((
+                widget::label(name),
+                Node {
+                    justify_self: JustifySelf::End,
+                    ..default()
+                },
+            ))
+            .id();
+        commands.entity(ui_root).add_children(&[label, widget]);
+    }
+
+    (ui_root, steppers)
 }
 
 fn global_volume_widget(
     button_assets: &ButtonAssets,
     texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
-    _directional_nav_map: ResMut<DirectionalNavigationMap>,
     commands: &mut Commands,
-) -> Entity {
+) -> (Entity, [Entity; 2]) {
     let ui_root = commands
         .spawn((
             Name::new("Global Volume Widget"),
@@ -150,9 +192,7 @@ fn global_volume_widget(
         .id();
     commands.entity(ui_root).add_child(plus_button);
 
-    // TODO: Proper nav grid for buttons
-
-    ui_root
+    (ui_root, [minus_button, plus_button])
 }
 
 const MIN_VOLUME: f32 = 0.0;
@@ -180,6 +220,156 @@ fn update_global_volume_label(
     label.0 = format!("{percent:3.0}%");
 }
 
+/// A setting of both [`StickSettings`], stepped through with the lower and raise buttons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+enum StickSetting {
+    InnerDeadzone,
+    OuterDeadzone,
+    Curve,
+    Sensitivity,
+}
+
+const DEADZONE_STEP: f32 = 0.05;
+const MAX_INNER_DEADZONE: f32 = 0.5;
+const MIN_OUTER_DEADZONE: f32 = 0.6;
+const SENSITIVITY_STEP: f32 = 0.1;
+const MIN_SENSITIVITY: f32 = 0.5;
+const MAX_SENSITIVITY: f32 = 2.0;
+
+impl StickSetting {
+    const ALL: [StickSetting; 4] = [
+        StickSetting::InnerDeadzone,
+        StickSetting::OuterDeadzone,
+        StickSetting::Curve,
+        StickSetting::Sensitivity,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            StickSetting::InnerDeadzone => "deadzone",
+            StickSetting::OuterDeadzone => "outer zone",
+            StickSetting::Curve => "curve",
+            StickSetting::Sensitivity => "sensitivity",
+        }
+    }
+
+    fn text(&self, settings: &StickSettings) -> String {
+        match self {
+            StickSetting::InnerDeadzone => format!("{:3.0}%", 100.0 * settings.inner_deadzone),
+            StickSetting::OuterDeadzone => format!("{:3.0}%", 100.0 * settings.outer_deadzone),
+            StickSetting::Curve => settings.curve.name().to_string(),
+            StickSetting::Sensitivity => format!("{:.1}x", settings.sensitivity),
+        }
+    }
+
+    /// Raises the setting by `steps`, or lowers it if negative. Curves wrap around.
+    fn step(&self, settings: &mut StickSettings, steps: i32) {
+        // Rounded, so repeated steps don't drift away from round numbers.
+        let stepped =
+            |value: f32, step: f32| ((value + step * steps as f32) * 100.0).round() / 100.0;
+
+        match self {
+            StickSetting::InnerDeadzone => {
+                settings.inner_deadzone =
+                    stepped(settings.inner_deadzone, DEADZONE_STEP).clamp(0.0, MAX_INNER_DEADZONE);
+            }
+            StickSetting::OuterDeadzone => {
+                settings.outer_deadzone =
+                    stepped(settings.outer_deadzone, DEADZONE_STEP).clamp(MIN_OUTER_DEADZONE, 1.0);
+            }
+            StickSetting::Curve => {
+                let curves = ResponseCurve::ALL;
+                let index = curves
+                    .iter()
+                    .position(|curve| *curve == settings.curve)
+                    .unwrap_or(0) as i32;
+                settings.curve = curves[(index + steps).rem_euclid(curves.len() as i32) as usize];
+            }
+            StickSetting::Sensitivity => {
+                settings.sensitivity = stepped(settings.sensitivity, SENSITIVITY_STEP)
+                    .clamp(MIN_SENSITIVITY, MAX_SENSITIVITY);
+            }
+        }
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct StickSettingLabel {
+    stick: Stick,
+    setting: StickSetting,
+}
+
+fn stick_setting_widget(
+    button_assets: &ButtonAssets,
+    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+    commands: &mut Commands,
+    stick: Stick,
+    setting: StickSetting,
+) -> (Entity, [Entity; 2]) {
+    let ui_root = commands
+        .spawn((
+            Name::new("Stick Setting Widget"),
+            Node {
+                justify_self: JustifySelf::Start,
+                ..default()
+            },
+        ))
+        .id();
+
+    let minus_button = commands
+        .spawn(widget::button_small(
+            button_assets,
+            texture_atlas_layouts,
+            "-",
+            move |_: On<Pointer<Click>>, mut input_map: ResMut<InputMap>| {
+                setting.step(input_map.stick_mut(stick), -1);
+            },
+        ))
+        .id();
+    commands.entity(ui_root).add_child(minus_button);
+
+    let current_value_display = commands
+        .spawn((
+            Name::new("Current Value"),
+            Node {
+                width: px(160),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            children![(widget::label(""), StickSettingLabel { stick, setting })],
+        ))
+        .id();
+    commands.entity(ui_root).add_child(current_value_display);
+
+    let plus_button = commands
+        .spawn(widget::button_small(
+            button_assets,
+            texture_atlas_layouts,
+            "+",
+            move |_: On<Pointer<Click>>, mut input_map: ResMut<InputMap>| {
+                setting.step(input_map.stick_mut(stick), 1);
+            },
+        ))
+        .id();
+    commands.entity(ui_root).add_child(plus_button);
+
+    (ui_root, [minus_button, plus_button])
+}
+
+fn update_stick_setting_labels(
+    input_map: Res<InputMap>,
+    mut labels: Query<(&mut Text, &StickSettingLabel)>,
+) {
+    for (mut text, label) in &mut labels {
+        text.0 = label.setting.text(input_map.stick(label.stick));
+    }
+}
+
+fn open_controls_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Controls);
+}
+
 fn go_back_on_click(
     _: On<Pointer<Click>>,
     screen: Res<State<Screen>>,
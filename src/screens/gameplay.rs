@@ -1,8 +1,8 @@
 //! The screen state for the main gameplay.
 
-use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy::prelude::*;
 
-use crate::gamepad::gamepad_just_pressed;
+use crate::input::{Action, action_just_pressed};
 use crate::{Pause, game::level::spawn_level, menus::Menu, screens::Screen};
 
 pub(super) fn plugin(app: &mut App) {
@@ -13,16 +13,14 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         (
             (pause, spawn_pause_overlay, open_pause_menu).run_if(
-                in_state(Screen::Gameplay).and(in_state(Menu::None)).and(
-                    input_just_pressed(KeyCode::KeyP)
-                        .or(input_just_pressed(KeyCode::Escape))
-                        .or(gamepad_just_pressed(GamepadButton::Start)),
-                ),
+                in_state(Screen::Gameplay)
+                    .and(in_state(Menu::None))
+                    .and(action_just_pressed(Action::Pause)),
             ),
             close_menu.run_if(
                 in_state(Screen::Gameplay)
                     .and(not(in_state(Menu::None)))
-                    .and(input_just_pressed(KeyCode::KeyP)),
+                    .and(action_just_pressed(Action::Pause)),
             ),
         ),
     );
@@ -114,6 +114,7 @@ where
         texture_atlas_layouts,
         text,
         action,
+        40.0,
         (Node {
             width: px(380),
             height: px(80),
@@ -124,6 +125,64 @@ where
     )
 }
 
+/// A wide, low button for longer text, e.g. dialogue choices, with an action defined as an
+/// [`Observer`].
+pub fn button_wide<E, B, M, I>(
+    button_assets: &ButtonAssets,
+    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+    text: impl Into<String>,
+    action: I,
+) -> impl Bundle
+where
+    E: EntityEvent,
+    B: Bundle,
+    I: IntoObserverSystem<E, B, M>,
+{
+    button_base(
+        button_assets,
+        texture_atlas_layouts,
+        text,
+        action,
+        40.0,
+        Node {
+            width: px(560),
+            height: px(64),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+    )
+}
+
+/// A compact button for dense lists, e.g. the controls page, with an action defined as an
+/// [`Observer`].
+pub fn button_compact<E, B, M, I>(
+    button_assets: &ButtonAssets,
+    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+    text: impl Into<String>,
+    action: I,
+) -> impl Bundle
+where
+    E: EntityEvent,
+    B: Bundle,
+    I: IntoObserverSystem<E, B, M>,
+{
+    button_base(
+        button_assets,
+        texture_atlas_layouts,
+        text,
+        action,
+        24.0,
+        Node {
+            width: px(280),
+            height: px(44),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+    )
+}
+
 /// A small square button with text and an action defined as an [`Observer`].
 pub fn button_small<E, B, M, I>(
     button_assets: &ButtonAssets,
@@ -141,6 +200,7 @@ where
         texture_atlas_layouts,
         text,
         action,
+        40.0,
         Node {
             width: px(30),
             height: px(30),
@@ -157,6 +217,7 @@ fn button_base<E, B, M, I>(
     texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
     text: impl Into<String>,
     action: I,
+    font_size: f32,
     button_bundle: impl Bundle,
 ) -> impl Bundle
 where
@@ -176,7 +237,7 @@ where
         Name::new("Button"),
         ButtonRoot,
         Node::default(),
-        Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
             parent
                 .spawn((
                     Name::new("Button Inner"),
@@ -195,7 +256,7 @@ where
                     children![(
                         Name::new("Button Text"),
                         Text(text),
-                        TextFont::from_font_size(40.0),
+                        TextFont::from_font_size(font_size),
                         TextColor(BUTTON_TEXT),
                         Node {
                             justify_self: JustifySelf::Center,